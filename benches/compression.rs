@@ -0,0 +1,99 @@
+/*************************************************************************
+* ph0llux:6a0f3b8d27c1e94f5d08a2b7e3c61f49d85b0e2a7c3f91d46b8e05a2c7d19f30
+*************************************************************************/
+//! compares the throughput of the single-threaded gzip/zstd writers with the [ParallelCompressor].
+//! Run with `cargo bench --features gzip,zstd --bench compression`.
+
+// - STD
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+// - external
+extern crate flate2;
+extern crate phollaits;
+extern crate zstd;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use phollaits::{CompressionFormat, HumanReadable, ParallelCompressor};
+
+const INPUT_SIZE: usize = 64 * 1024 * 1024;
+const ITERATIONS: u32 = 3;
+
+fn main() {
+	let input = bench_input();
+	let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+
+	bench("gzip single-threaded (flate2)", &input, || {
+		let mut encoder = GzEncoder::new(Vec::new(), Compression::new(6));
+		encoder.write_all(&input).unwrap();
+		encoder.finish().unwrap().len()
+	});
+	for n in worker_counts(workers) {
+		bench(&format!("gzip parallel ({} workers)", n), &input, || {
+			let mut compressor = ParallelCompressor::new(Vec::new(), CompressionFormat::Gzip).workers(n);
+			compressor.write_all(&input).unwrap();
+			compressor.finish().unwrap().len()
+		});
+	}
+
+	bench("zstd single-threaded (zstd)", &input, || {
+		let mut encoder = zstd::Encoder::new(Vec::new(), 3).unwrap();
+		encoder.write_all(&input).unwrap();
+		encoder.finish().unwrap().len()
+	});
+	for n in worker_counts(workers) {
+		bench(&format!("zstd parallel ({} workers)", n), &input, || {
+			let mut compressor = ParallelCompressor::new(Vec::new(), CompressionFormat::Zstd).workers(n);
+			compressor.write_all(&input).unwrap();
+			compressor.finish().unwrap().len()
+		});
+	}
+}
+
+fn bench<F: FnMut() -> usize>(name: &str, input: &[u8], mut f: F) {
+	let mut best = Duration::MAX;
+	let mut compressed_size = 0;
+	for _ in 0..ITERATIONS {
+		let start = Instant::now();
+		compressed_size = f();
+		best = best.min(start.elapsed());
+	}
+	let throughput = input.len() as f64 / best.as_secs_f64();
+	println!("{:<35} {:>10}/s  {:>8.3}s  ratio {:.3}",
+		name, throughput.bytes_as_hrb(), best.as_secs_f64(), compressed_size as f64 / input.len() as f64);
+}
+
+fn worker_counts(max: usize) -> Vec<usize> {
+	let mut counts = vec![1];
+	while counts[counts.len() - 1] * 2 <= max {
+		counts.push(counts[counts.len() - 1] * 2);
+	}
+	if counts[counts.len() - 1] != max {
+		counts.push(max);
+	}
+	counts
+}
+
+// text-like data (pseudo-random words), so the compressors have something to do.
+fn bench_input() -> Vec<u8> {
+	const WORDS: [&str; 16] = [
+		"archive", "digest", "phollaits", "tar", "block", "stream", "worker", "evidence", "the", "a", "of", "and",
+		"example", "compression", "thread", "buffer",
+	];
+	let mut input = Vec::with_capacity(INPUT_SIZE + 64);
+	let mut state = 0x2545_f491_4f6c_dd1du64;
+	while input.len() < INPUT_SIZE {
+		// xorshift64
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		input.extend_from_slice(WORDS[(state % 16) as usize].as_bytes());
+		input.push(if state % 11 == 0 { b'\n' } else { b' ' });
+		if state % 5 == 0 {
+			input.extend_from_slice(format!("{} ", state % 100_000).as_bytes());
+		}
+	}
+	input.truncate(INPUT_SIZE);
+	input
+}
\ No newline at end of file
@@ -0,0 +1,64 @@
+/*************************************************************************
+* ph0llux:e2b7a04f9c83d16e5a0f2c7b94d18e63a5c0f7b2d9e41a86c3b05f2e7d9a14c8
+*************************************************************************/
+//! compares the throughput of [HashExt] with the former 1 KiB buffer, the default buffer, a large buffer and (with the
+//! feature "mmap") the memory mapped hashing.
+//! Run with `cargo bench --bench hash` or `cargo bench --features mmap --bench hash`.
+
+// - STD
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// - external
+extern crate phollaits;
+
+use phollaits::{HashAlgorithm, HashExt, HumanReadable, DEFAULT_HASH_BUFFER_SIZE};
+
+const INPUT_SIZE: usize = 256 * 1024 * 1024;
+const ITERATIONS: u32 = 3;
+
+fn main() {
+	let path = std::env::temp_dir().join("phollaits_hash_bench.bin");
+	fs::write(&path, bench_input()).unwrap();
+
+	for algorithm in [HashAlgorithm::Md5, HashAlgorithm::Sha256] {
+		for buffer_size in [1024, DEFAULT_HASH_BUFFER_SIZE, 1024 * 1024] {
+			bench(&format!("{} buffer {}", algorithm, buffer_size.bytes_as_hrb()), &path, || {
+				let mut file = File::open(&path).unwrap();
+				file.hash_with_buffer_size(algorithm, buffer_size).unwrap()
+			});
+		}
+		#[cfg(feature = "mmap")]
+		bench(&format!("{} mmap", algorithm), &path, || {
+			phollaits::mmap_digest(&path, algorithm).unwrap().to_hex()
+		});
+	}
+	fs::remove_file(&path).unwrap();
+}
+
+fn bench<F: FnMut() -> String>(name: &str, path: &Path, mut f: F) {
+	let mut best = Duration::MAX;
+	let mut digest = String::new();
+	for _ in 0..ITERATIONS {
+		let start = Instant::now();
+		digest = f();
+		best = best.min(start.elapsed());
+	}
+	let size = fs::metadata(path).unwrap().len();
+	let throughput = size as f64 / best.as_secs_f64();
+	println!("{:<30} {:>10}/s  {:>8.3}s  {}", name, throughput.bytes_as_hrb(), best.as_secs_f64(), &digest[..16]);
+}
+
+// pseudo-random data (xorshift64).
+fn bench_input() -> Vec<u8> {
+	let mut input = Vec::with_capacity(INPUT_SIZE);
+	let mut state = 0x2545_f491_4f6c_dd1du64;
+	while input.len() < INPUT_SIZE {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		input.extend_from_slice(&state.to_le_bytes());
+	}
+	input
+}
@@ -4,16 +4,19 @@
 //!tar Module.
 
 // - STD
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
 use std::fs::File;
 use std::time::SystemTime;
 use std::io;
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
 
 // - external
-use tar::{Builder,Header,EntryType};
+use tar::{Archive,Builder,Header,EntryType};
 
 // - internal
-use super::{PhollaitsError, PhollaitsErrorKind, Result};
+use super::{file_permissions, Digest, HashAlgorithm, HashDataExt, HashExt, HashingReader, PhollaitsError, PhollaitsErrorKind, Result};
 
 /// Trait implements some extensions for the [Builder](https://docs.rs/tar/0.4.30/tar/struct.Builder.html)-struct of the [tar](https://docs.rs/tar/0.4.30/tar/) crate.
 pub trait TarBuilderExt {
@@ -65,7 +68,7 @@ pub trait TarBuilderExt {
 	fn close_archive(self) -> Result<()>;
 }
 
-impl TarBuilderExt for Builder<File> {
+impl<W: io::Write> TarBuilderExt for Builder<W> {
 	fn append_file_directly<P: Into<String>>(&mut self, path: P) -> Result<()> {
 		let path = path.into();
 		if Path::new(&path).is_absolute() {
@@ -113,50 +116,617 @@ impl TarBuilderExt for Builder<File> {
 	}
 }
 
-impl TarBuilderExt for Builder<Box<dyn io::Write>> {
-	fn append_file_directly<P: Into<String>>(&mut self, path: P) -> Result<()> {
+/// A wrapper around the [Builder](https://docs.rs/tar/0.4.30/tar/struct.Builder.html)-struct, which stores byte-identical
+/// contents only once. Every appended file (or text) will be hashed with SHA-256 (as typed [Digest], see
+/// [HashExt::digest_with]). If the content was already stored in the archive with the same permissions, a hardlink entry
+/// pointing at the first copy will be written instead of the data.
+pub struct DedupBuilder<W: io::Write> {
+	builder: Builder<W>,
+	// the first copy of every stored content and permissions.
+	stored_content: HashMap<(Digest, u32), String>,
+	// the sizes and permissions of the stored contents: only files matching one of them could be a duplicate.
+	stored_sizes: HashSet<(u64, u32)>,
+	bytes_saved: u64,
+}
+
+impl<W: io::Write> DedupBuilder<W> {
+	/// creates a new deduplicating archive builder, which writes the archive to the given writer.
+	pub fn new(obj: W) -> DedupBuilder<W> {
+		DedupBuilder {
+			builder: Builder::new(obj),
+			stored_content: HashMap::new(),
+			stored_sizes: HashSet::new(),
+			bytes_saved: 0,
+		}
+	}
+
+	/// returns the number of bytes, which were not written to the archive so far (because the content was already stored).
+	pub fn bytes_saved(&self) -> u64 {
+		self.bytes_saved
+	}
+
+	/// appends a file to an archive. If a file with the same content was already appended, a hardlink entry will be written.
+	/// Directories and other non-regular files will be appended like [TarBuilderExt::append_file_directly] does.
+	/// A file is hashed while it is written to the archive. Only if a file with the same size and permissions was already
+	/// stored, the file has to be read twice: once to check for a duplicate, and once more to append the data.
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	///
+	/// use phollaits::*;
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	///		let file = fs::File::create("/tmp/dedup_archive.tar").unwrap();
+	/// 	let mut b = DedupBuilder::new(file);
+	/// 	b.append_file_directly("assets/example.jpg")?;
+	/// 	b.append_file_directly("assets/example.jpg")?; // will be stored as hardlink
+	/// 	let bytes_saved = b.close_archive()?;
+	/// 	assert_eq!(bytes_saved, fs::metadata("assets/example.jpg").unwrap().len());
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn append_file_directly<P: Into<String>>(&mut self, path: P) -> Result<()> {
 		let path = path.into();
-		if Path::new(&path).is_absolute() {
-			match self.append_path_with_name(&path, &path[1..]) {
-				Ok(x) => return Ok(x),
-				Err(e) => return Err(PhollaitsError::new(
+		let name_in_archive = if Path::new(&path).is_absolute() {
+			path[1..].to_string()
+		} else {
+			path.clone()
+		};
+		let metadata = match std::fs::metadata(&path) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (metadata); {}", e)))
+		};
+		if !metadata.is_file() {
+			return self.builder.append_file_directly(path);
+		}
+		let mut file = match File::open(&path) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (open); {}", e)))
+		};
+		// files with the same content, but different permissions are stored separately, as a hardlink shares the
+		// permissions of the first copy.
+		let permissions = file_permissions(&metadata);
+		let mut header = Header::new_gnu();
+		header.set_metadata(&metadata);
+		if self.stored_sizes.contains(&(metadata.len(), permissions)) {
+			let key = (file.digest_with(HashAlgorithm::Sha256)?, permissions);
+			if let Some(first_copy) = self.stored_content.get(&key) {
+				header.set_entry_type(EntryType::Link);
+				header.set_size(0);
+				match self.builder.append_link(&mut header, &name_in_archive, first_copy) {
+					Ok(_) => (),
+					Err(e) => return Err(PhollaitsError::new(
+						PhollaitsErrorKind::ArchiveError,
+						format!("Error while trying to append file directly (append_link); {}", e)))
+				};
+				self.bytes_saved += metadata.len();
+				return Ok(());
+			}
+			// the stored bytes are hashed again while appending (from the same handle), so the recorded digest always
+			// belongs to the archived content, even if the file was changed in the meantime.
+			if let Err(e) = file.seek(SeekFrom::Start(0)) {
+				return Err(PhollaitsError::new(
 					PhollaitsErrorKind::ArchiveError,
-					format!("Error while trying to append file directly (append_path_with_name); {}", e.to_string())))
+					format!("Error while trying to append file directly (seek); {}", e)));
+			}
+		}
+		let mut reader = HashingReader::new(file, &[HashAlgorithm::Sha256]);
+		if let Err(e) = self.builder.append_data(&mut header, &name_in_archive, &mut reader) {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (append_data); {}", e)));
+		}
+		if reader.byte_count() != metadata.len() {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly; {} changed while appending", path)));
+		}
+		let mut digests = reader.finalize();
+		if let Some(digest) = digests.remove(&HashAlgorithm::Sha256) {
+			self.stored_content.insert((digest, permissions), name_in_archive);
+			self.stored_sizes.insert((metadata.len(), permissions));
+		}
+		Ok(())
+	}
+
+	/// appends a text (string) to an archive (see [TarBuilderExt::append_text]). If the same content was already appended, a
+	/// hardlink entry will be written.
+	pub fn append_text<F: Into<String>, T: Into<String>>(&mut self, filename: F, text: T) -> Result<()> {
+		let filename = filename.into();
+		let text = text.into();
+		let key = (text.digest_with(HashAlgorithm::Sha256)?, 420);
+		if let Some(first_copy) = self.stored_content.get(&key) {
+			let mut header = Header::new_gnu();
+			header.set_size(0);
+			header.set_mode(420);
+			header.set_entry_type(EntryType::Link);
+			if let Ok(n) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+				header.set_mtime(n.as_secs())
 			};
-		} else {
-			match self.append_path(path) {
-				Ok(x) => return Ok(x),
+			match self.builder.append_link(&mut header, &filename, first_copy) {
+				Ok(_) => (),
 				Err(e) => return Err(PhollaitsError::new(
 					PhollaitsErrorKind::ArchiveError,
-					format!("Error while trying to append file directly (append_path); {}", e.to_string())))
+					format!("Error while trying to append text (append_link); {}", e)))
 			};
-		};
+			self.bytes_saved += text.len() as u64;
+			return Ok(());
+		}
+		let size = text.len() as u64;
+		self.builder.append_text(filename.clone(), text)?;
+		self.stored_sizes.insert((size, key.1));
+		self.stored_content.insert(key, filename);
+		Ok(())
 	}
-	fn append_text<F: Into<String>, T: Into<String>>(&mut self, filename: F, text: T) -> Result<()> {
-		let filename = filename.into();
-		let text = text.into();
-		let mut header = Header::new_gnu();
-		header.set_size(text.len() as u64); //header size must be == size of string in bytes.
-		header.set_cksum();
-		header.set_mode(420); //the value will be converted into octal (420 -> 644, 40 -> 50, ...)
-		header.set_entry_type(EntryType::file());
-		match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) { //sets the current time as mtime for the file
-			Ok(n) => header.set_mtime(n.as_secs()),
-			Err(_) => ()
+
+	/// finishes the archive and returns the number of bytes saved by deduplication.
+	pub fn close_archive(self) -> Result<u64> {
+		let bytes_saved = self.bytes_saved;
+		self.builder.close_archive()?;
+		Ok(bytes_saved)
+	}
+}
+
+/// The type of an entry in a [VirtualFileSystem].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VirtualEntryType {
+	File,
+	Directory,
+	/// a symbolic link, pointing to the given target.
+	Symlink(PathBuf),
+	/// a character device with the given major and minor number.
+	CharDevice { major: u32, minor: u32 },
+	/// a block device with the given major and minor number.
+	BlockDevice { major: u32, minor: u32 },
+	/// a named pipe.
+	Fifo,
+}
+
+/// An entry of a [VirtualFileSystem], containing the data and the metadata which will be kept in the tar header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualEntry {
+	pub entry_type: VirtualEntryType,
+	pub data: Vec<u8>,
+	/// the "unix-like" file permissions (e.g. 420 -> 644).
+	pub mode: u32,
+	pub uid: u64,
+	pub gid: u64,
+	pub mtime: u64,
+}
+
+impl VirtualEntry {
+	/// creates a regular file entry with the file permissions 644 and the current time as mtime.
+	pub fn file<D: Into<Vec<u8>>>(data: D) -> VirtualEntry {
+		VirtualEntry {
+			entry_type: VirtualEntryType::File,
+			data: data.into(),
+			mode: 420,
+			uid: 0,
+			gid: 0,
+			mtime: current_mtime(),
+		}
+	}
+
+	/// creates a directory entry with the file permissions 755 and the current time as mtime.
+	pub fn directory() -> VirtualEntry {
+		VirtualEntry {
+			entry_type: VirtualEntryType::Directory,
+			data: Vec::new(),
+			mode: 493,
+			uid: 0,
+			gid: 0,
+			mtime: current_mtime(),
+		}
+	}
+
+	/// creates a symlink entry with the file permissions 777 and the current time as mtime.
+	pub fn symlink<P: Into<PathBuf>>(target: P) -> VirtualEntry {
+		VirtualEntry {
+			entry_type: VirtualEntryType::Symlink(target.into()),
+			data: Vec::new(),
+			mode: 511,
+			uid: 0,
+			gid: 0,
+			mtime: current_mtime(),
+		}
+	}
+}
+
+/// An in-memory filesystem, which can be converted to and from a tar archive. All paths are stored relative (a leading
+/// "/" and "." components will be removed); paths containing ".." are rejected. Missing parent directories will be
+/// created implicitly in both directions.
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use std::collections::BTreeMap;
+/// use std::path::PathBuf;
+/// use tar::Builder;
+///
+/// fn main() -> Result<()> {
+/// 	let mut archive = Vec::new();
+/// 	let mut b = Builder::new(&mut archive);
+/// 	b.append_text("docs/example01.txt", "this is an example text")?;
+/// 	b.close_archive()?;
+///
+/// 	let vfs = VirtualFileSystem::from_tar(&archive[..])?;
+/// 	assert_eq!(vfs.get("docs").unwrap().entry_type, VirtualEntryType::Directory);
+///
+/// 	let mut expected = BTreeMap::new();
+/// 	expected.insert(PathBuf::from("docs/example01.txt"), b"this is an example text".to_vec());
+/// 	assert_eq!(vfs.clone().into_contents(), expected);
+///
+/// 	// ...and back to an archive again.
+/// 	let roundtrip = VirtualFileSystem::from_tar(&vfs.to_tar()?[..])?;
+/// 	assert_eq!(roundtrip, vfs);
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VirtualFileSystem {
+	entries: BTreeMap<PathBuf, VirtualEntry>,
+}
+
+impl VirtualFileSystem {
+	/// creates an empty virtual filesystem.
+	pub fn new() -> VirtualFileSystem {
+		VirtualFileSystem::default()
+	}
+
+	/// inserts an entry at the given path and returns the previous entry (if any). Returns an error, if the path
+	/// contains "..".
+	pub fn insert<P: AsRef<Path>>(&mut self, path: P, entry: VirtualEntry) -> Result<Option<VirtualEntry>> {
+		Ok(self.entries.insert(normalize_archive_path(path.as_ref())?, entry))
+	}
+
+	/// inserts a regular file (see [VirtualEntry::file]) at the given path.
+	pub fn insert_file<P: AsRef<Path>, D: Into<Vec<u8>>>(&mut self, path: P, data: D) -> Result<Option<VirtualEntry>> {
+		self.insert(path, VirtualEntry::file(data))
+	}
+
+	/// returns the entry at the given path.
+	pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&VirtualEntry> {
+		self.entries.get(&normalize_archive_path(path.as_ref()).ok()?)
+	}
+
+	/// removes the entry at the given path.
+	pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<VirtualEntry> {
+		self.entries.remove(&normalize_archive_path(path.as_ref()).ok()?)
+	}
+
+	/// returns all entries (including the directories).
+	pub fn entries(&self) -> &BTreeMap<PathBuf, VirtualEntry> {
+		&self.entries
+	}
+
+	/// returns the contents of all regular files.
+	pub fn into_contents(self) -> BTreeMap<PathBuf, Vec<u8>> {
+		self.entries
+			.into_iter()
+			.filter(|(_, entry)| entry.entry_type == VirtualEntryType::File)
+			.map(|(path, entry)| (path, entry.data))
+			.collect()
+	}
+
+	/// reads a tar archive into a virtual filesystem. Hardlinks will be resolved to a copy of their target. Returns an
+	/// error, if the path of an entry (or the target of a hardlink) contains "..".
+	/// # Example
+	/// ```
+	/// extern crate tar;
+	/// extern crate phollaits;
+	///
+	/// use phollaits::*;
+	/// use tar::{Builder, EntryType, Header};
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut vfs = VirtualFileSystem::new();
+	/// 	let mut fifo = VirtualEntry::file(Vec::new());
+	/// 	fifo.entry_type = VirtualEntryType::Fifo;
+	/// 	vfs.insert("dev/pipe", fifo)?;
+	/// 	assert!(vfs.insert_file("dev/../escape", "data").is_err());
+	/// 	let roundtrip = VirtualFileSystem::from_tar(&vfs.to_tar()?[..])?;
+	/// 	assert_eq!(roundtrip.get("dev/pipe").unwrap().entry_type, VirtualEntryType::Fifo);
+	///
+	/// 	// tar::Builder refuses to write such a path, so the header is written manually.
+	/// 	let mut header = Header::new_gnu();
+	/// 	header.as_gnu_mut().unwrap().name[..9].copy_from_slice(b"../escape");
+	/// 	header.set_entry_type(EntryType::file());
+	/// 	header.set_size(4);
+	/// 	header.set_cksum();
+	/// 	let mut archive = Vec::new();
+	/// 	let mut b = Builder::new(&mut archive);
+	/// 	b.append(&header, &b"data"[..]).unwrap();
+	/// 	b.close_archive()?;
+	/// 	assert!(VirtualFileSystem::from_tar(&archive[..]).is_err());
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn from_tar<R: io::Read>(reader: R) -> Result<VirtualFileSystem> {
+		let mut vfs = VirtualFileSystem::new();
+		let mut archive = Archive::new(reader);
+		let entries = match archive.entries() {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to read archive (entries); {}", e)))
 		};
-		match self.append_data(&mut header, &filename, text.as_bytes()) {
-				Ok(x) => return Ok(x),
+		for entry in entries {
+			let mut entry = match entry {
+				Ok(x) => x,
 				Err(e) => return Err(PhollaitsError::new(
 					PhollaitsErrorKind::ArchiveError,
-					format!("Error while trying to append text; {}", e.to_string())))
+					format!("Error while trying to read archive (entry); {}", e)))
+			};
+			let path = match entry.path() {
+				Ok(x) => normalize_archive_path(&x)?,
+				Err(e) => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::ArchiveError,
+					format!("Error while trying to read archive (path); {}", e)))
+			};
+			if path.as_os_str().is_empty() {
+				continue;
+			}
+			let header = entry.header();
+			let mut virtual_entry = VirtualEntry {
+				entry_type: VirtualEntryType::File,
+				data: Vec::new(),
+				mode: header.mode().unwrap_or(420),
+				uid: header.uid().unwrap_or(0),
+				gid: header.gid().unwrap_or(0),
+				mtime: header.mtime().unwrap_or(0),
 			};
+			let entry_type = header.entry_type();
+			let link_name = match entry.link_name() {
+				Ok(x) => x.map(|x| x.into_owned()),
+				Err(e) => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::ArchiveError,
+					format!("Error while trying to read archive (link_name); {}", e)))
+			};
+			match entry_type {
+				EntryType::Regular | EntryType::Continuous => {
+					if let Err(e) = entry.read_to_end(&mut virtual_entry.data) {
+						return Err(PhollaitsError::new(
+							PhollaitsErrorKind::ArchiveError,
+							format!("Error while trying to read archive (data); {}", e)))
+					};
+				},
+				EntryType::Directory => virtual_entry.entry_type = VirtualEntryType::Directory,
+				EntryType::Symlink => {
+					virtual_entry.entry_type = VirtualEntryType::Symlink(link_name.unwrap_or_default());
+				},
+				EntryType::Link => {
+					let target = normalize_archive_path(&link_name.unwrap_or_default())?;
+					match vfs.entries.get(&target) {
+						Some(x) => virtual_entry.data = x.data.clone(),
+						None => return Err(PhollaitsError::new(
+							PhollaitsErrorKind::ArchiveError,
+							format!("Error while trying to read archive; hardlink target {} not found", target.display())))
+					}
+				},
+				EntryType::Char | EntryType::Block => {
+					let (major, minor) = match (header.device_major(), header.device_minor()) {
+						(Ok(major), Ok(minor)) => (major.unwrap_or(0), minor.unwrap_or(0)),
+						(Err(e), _) | (_, Err(e)) => return Err(PhollaitsError::new(
+							PhollaitsErrorKind::ArchiveError,
+							format!("Error while trying to read archive (device numbers); {}", e)))
+					};
+					virtual_entry.entry_type = if entry_type == EntryType::Char {
+						VirtualEntryType::CharDevice { major, minor }
+					} else {
+						VirtualEntryType::BlockDevice { major, minor }
+					};
+				},
+				EntryType::Fifo => virtual_entry.entry_type = VirtualEntryType::Fifo,
+				// e.g. global pax headers, which do not describe an entry.
+				_ => continue,
+			}
+			vfs.create_parent_directories(&path, virtual_entry.mtime);
+			vfs.entries.insert(path, virtual_entry);
+		}
+		Ok(vfs)
 	}
-	fn close_archive(mut self) -> Result<()> {
-		match self.finish() {
-			Ok(x) => return Ok(x),
-			Err(e) => return Err(PhollaitsError::new(
+
+	/// writes all entries (and the implicit parent directories) to the given [Builder](https://docs.rs/tar/0.4.30/tar/struct.Builder.html).
+	pub fn append_to_builder<W: io::Write>(&self, builder: &mut Builder<W>) -> Result<()> {
+		// the implicit parent directories are collected separately, so the entries (and their data) are not cloned.
+		let mut parents = BTreeMap::new();
+		for (path, entry) in &self.entries {
+			add_parent_directories(&self.entries, &mut parents, path, entry.mtime);
+		}
+		let entries: BTreeMap<&PathBuf, &VirtualEntry> = self.entries.iter().chain(&parents).collect();
+		for (path, entry) in entries {
+			let mut header = Header::new_gnu();
+			header.set_mode(entry.mode);
+			header.set_uid(entry.uid);
+			header.set_gid(entry.gid);
+			header.set_mtime(entry.mtime);
+			let result = match &entry.entry_type {
+				VirtualEntryType::File => {
+					header.set_entry_type(EntryType::file());
+					header.set_size(entry.data.len() as u64);
+					builder.append_data(&mut header, path, &entry.data[..])
+				},
+				VirtualEntryType::Directory => {
+					header.set_entry_type(EntryType::dir());
+					header.set_size(0);
+					builder.append_data(&mut header, path, io::empty())
+				},
+				VirtualEntryType::Symlink(target) => {
+					header.set_entry_type(EntryType::symlink());
+					header.set_size(0);
+					builder.append_link(&mut header, path, target)
+				},
+				VirtualEntryType::CharDevice { major, minor } | VirtualEntryType::BlockDevice { major, minor } => {
+					header.set_entry_type(match entry.entry_type {
+						VirtualEntryType::CharDevice { .. } => EntryType::Char,
+						_ => EntryType::Block,
+					});
+					header.set_size(0);
+					match header.set_device_major(*major).and_then(|_| header.set_device_minor(*minor)) {
+						Ok(_) => builder.append_data(&mut header, path, io::empty()),
+						Err(e) => Err(e),
+					}
+				},
+				VirtualEntryType::Fifo => {
+					header.set_entry_type(EntryType::Fifo);
+					header.set_size(0);
+					builder.append_data(&mut header, path, io::empty())
+				},
+			};
+			if let Err(e) = result {
+				return Err(PhollaitsError::new(
+					PhollaitsErrorKind::ArchiveError,
+					format!("Error while trying to append virtual entry {}; {}", path.display(), e)))
+			};
+		}
+		Ok(())
+	}
+
+	/// writes the virtual filesystem as tar archive into a [Vec].
+	/// # Example
+	/// ```
+	/// extern crate tar;
+	/// extern crate phollaits;
+	///
+	/// use phollaits::*;
+	/// use std::collections::BTreeMap;
+	/// use std::convert::TryFrom;
+	/// use std::path::PathBuf;
+	/// use tar::Archive;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut contents = BTreeMap::new();
+	/// 	contents.insert(PathBuf::from("src/lib/mod.rs"), b"pub mod a;".to_vec());
+	/// 	let archive = VirtualFileSystem::try_from(contents)?.to_tar()?;
+	///
+	/// 	let mut archive = Archive::new(&archive[..]);
+	/// 	let paths: Vec<PathBuf> = archive.entries().unwrap().map(|e| e.unwrap().path().unwrap().into_owned()).collect();
+	/// 	assert_eq!(paths, vec![PathBuf::from("src"), PathBuf::from("src/lib"), PathBuf::from("src/lib/mod.rs")]);
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn to_tar(&self) -> Result<Vec<u8>> {
+		let mut archive = Vec::new();
+		let mut builder = Builder::new(&mut archive);
+		self.append_to_builder(&mut builder)?;
+		builder.close_archive()?;
+		Ok(archive)
+	}
+
+	fn create_parent_directories(&mut self, path: &Path, mtime: u64) {
+		let mut parents = BTreeMap::new();
+		add_parent_directories(&self.entries, &mut parents, path, mtime);
+		self.entries.append(&mut parents);
+	}
+}
+
+// adds the parent directories of the path, which are neither in the entries nor in the parents yet, to the parents.
+fn add_parent_directories(
+	entries: &BTreeMap<PathBuf, VirtualEntry>,
+	parents: &mut BTreeMap<PathBuf, VirtualEntry>,
+	path: &Path,
+	mtime: u64) {
+	let mut parent = path.parent();
+	while let Some(directory) = parent {
+		if directory.as_os_str().is_empty() || entries.contains_key(directory) || parents.contains_key(directory) {
+			break;
+		}
+		let mut entry = VirtualEntry::directory();
+		entry.mtime = mtime;
+		parents.insert(directory.to_path_buf(), entry);
+		parent = directory.parent();
+	}
+}
+
+impl TryFrom<BTreeMap<PathBuf, Vec<u8>>> for VirtualFileSystem {
+	type Error = PhollaitsError;
+
+	fn try_from(contents: BTreeMap<PathBuf, Vec<u8>>) -> Result<VirtualFileSystem> {
+		let mut vfs = VirtualFileSystem::new();
+		for (path, data) in contents {
+			vfs.insert_file(path, data)?;
+		}
+		Ok(vfs)
+	}
+}
+
+/// wraps the reader with a decoder, if the data starts with the magic bytes of a gzip (feature "gzip") or zstd (feature
+/// "zstd") stream. Uncompressed data will be returned as it is.
+/// # Example
+/// ```
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+/// 	let file = fs::File::open("assets/example.jpg").unwrap();
+/// 	let mut reader = auto_decompress(file)?;
+/// 	assert_eq!(reader.sha256sum()?, "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793");
+/// 	Ok(())
+/// }
+/// ```
+pub fn auto_decompress<'a, R: io::Read + 'a>(reader: R) -> Result<Box<dyn io::Read + 'a>> {
+	let mut reader = BufReader::new(reader);
+	let magic = match reader.fill_buf() {
+		Ok(x) => x.iter().take(4).cloned().collect::<Vec<u8>>(),
+		Err(e) => return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to detect compression; {}", e)))
+	};
+	if magic.starts_with(&GZIP_MAGIC) {
+		#[cfg(feature = "gzip")]
+		return Ok(Box::new(flate2::read::MultiGzDecoder::new(reader)));
+		#[cfg(not(feature = "gzip"))]
+		return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			"Error while trying to detect compression; gzip support requires the \"gzip\" feature"));
+	}
+	if magic.starts_with(&ZSTD_MAGIC) {
+		#[cfg(feature = "zstd")]
+		return match zstd::Decoder::with_buffer(reader) {
+			Ok(x) => Ok(Box::new(x)),
+			Err(e) => Err(PhollaitsError::new(
 				PhollaitsErrorKind::ArchiveError,
-				format!("Error while trying to append text; {}", e.to_string())))
+				format!("Error while trying to detect compression; {}", e)))
 		};
+		#[cfg(not(feature = "zstd"))]
+		return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			"Error while trying to detect compression; zstd support requires the \"zstd\" feature"));
+	}
+	Ok(Box::new(reader))
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+fn current_mtime() -> u64 {
+	match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+		Ok(n) => n.as_secs(),
+		Err(_) => 0
+	}
+}
+
+// removes the root and "." components, so the path can be used inside of an archive. Paths with ".." components are
+// rejected, as they could point outside of the archive or collide with other entries.
+fn normalize_archive_path(path: &Path) -> Result<PathBuf> {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::Normal(x) => normalized.push(x),
+			Component::ParentDir => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to normalize archive path {}; \"..\" is not allowed", path.display()))),
+			Component::RootDir | Component::CurDir | Component::Prefix(_) => (),
+		}
 	}
+	Ok(normalized)
 }
\ No newline at end of file
@@ -0,0 +1,539 @@
+/*************************************************************************
+* ph0llux:b41e7d02c9a83f56e0d17b94a2c65f38e9d0a7b13c4f82e65a9d10b7f3c2e846
+*************************************************************************/
+//!async archive Module (tar archives on top of tokio's [AsyncRead]/[AsyncWrite]).
+
+// - STD
+use std::cmp;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::SystemTime;
+
+// - internal
+use super::{PhollaitsError, PhollaitsErrorKind, Result};
+
+// - external
+use tar::{EntryType, Header, HeaderMode};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+
+const BLOCK_SIZE: u64 = 512;
+const GNU_LONG_LINK: &[u8] = b"././@LongLink";
+// the maximum size of an extension entry (e.g. a GNU long name or PAX header), which will be read into memory.
+const MAX_EXTENSION_SIZE: u64 = 1024 * 1024;
+
+/// An async counterpart of the tar [Builder](https://docs.rs/tar/0.4.30/tar/struct.Builder.html) (with the methods of
+/// [TarBuilderExt](crate::TarBuilderExt)), which writes to an [AsyncWrite].
+/// # Example
+/// ```
+/// extern crate tokio;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// 	runtime.block_on(async {
+/// 		let file = tokio::fs::File::create("/tmp/async_archive.tar").await.unwrap();
+/// 		let mut b = AsyncTarBuilder::new(file);
+/// 		b.append_file_directly("assets/example.jpg").await?;
+/// 		b.append_text("/home/ph0llux/example01.txt", "this is an example text").await?;
+/// 		b.append_stream("stream.txt", &b"data from any AsyncRead"[..], 23).await?;
+/// 		b.close_archive().await?;
+///
+/// 		let file = tokio::fs::File::open("/tmp/async_archive.tar").await.unwrap();
+/// 		let mut reader = AsyncTarReader::new(file);
+/// 		let mut names = Vec::new();
+/// 		while let Some(entry) = reader.next_entry().await? {
+/// 			names.push(entry.path().to_string_lossy().to_string());
+/// 		}
+/// 		assert_eq!(names, vec!["assets/example.jpg", "home/ph0llux/example01.txt", "stream.txt"]);
+/// 		Ok(())
+/// 	})
+/// }
+/// ```
+pub struct AsyncTarBuilder<W: AsyncWrite + Unpin> {
+	obj: W,
+	mode: HeaderMode,
+	follow_symlinks: bool,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncTarBuilder<W> {
+	/// creates a new async archive builder, which writes the archive to the given writer.
+	pub fn new(obj: W) -> AsyncTarBuilder<W> {
+		AsyncTarBuilder {
+			obj,
+			mode: HeaderMode::Complete,
+			follow_symlinks: true,
+		}
+	}
+
+	/// sets the mode which is used to set the metadata of appended files (see
+	/// [Builder::mode](https://docs.rs/tar/0.4.30/tar/struct.Builder.html#method.mode)).
+	pub fn mode(&mut self, mode: HeaderMode) {
+		self.mode = mode;
+	}
+
+	/// follow symlinks when appending files (default: true, see
+	/// [Builder::follow_symlinks](https://docs.rs/tar/0.4.30/tar/struct.Builder.html#method.follow_symlinks)).
+	pub fn follow_symlinks(&mut self, follow: bool) {
+		self.follow_symlinks = follow;
+	}
+
+	/// appends a file to an archive (see [TarBuilderExt::append_file_directly](crate::TarBuilderExt::append_file_directly)).
+	pub async fn append_file_directly<P: Into<String>>(&mut self, path: P) -> Result<()> {
+		let path = path.into();
+		let name = if Path::new(&path).is_absolute() { path[1..].to_string() } else { path.clone() };
+		let metadata = if self.follow_symlinks {
+			tokio::fs::metadata(&path).await
+		} else {
+			tokio::fs::symlink_metadata(&path).await
+		};
+		let metadata = match metadata {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (metadata); {}", e)))
+		};
+		let mut header = Header::new_gnu();
+		header.set_metadata_in_mode(&metadata, self.mode);
+		if metadata.file_type().is_symlink() {
+			let target = match tokio::fs::read_link(&path).await {
+				Ok(x) => x,
+				Err(e) => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::ArchiveError,
+					format!("Error while trying to append file directly (read_link); {}", e)))
+			};
+			return self.append_link(&mut header, name, target).await;
+		}
+		if metadata.is_dir() {
+			return self.append_data(&mut header, name, tokio::io::empty()).await;
+		}
+		let file = match tokio::fs::File::open(&path).await {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (open); {}", e)))
+		};
+		self.append_data(&mut header, name, file).await
+	}
+
+	/// appends a text (string) to an archive (see [TarBuilderExt::append_text](crate::TarBuilderExt::append_text)).
+	pub async fn append_text<F: Into<String>, T: Into<String>>(&mut self, filename: F, text: T) -> Result<()> {
+		let text = text.into();
+		self.append_stream(filename, text.as_bytes(), text.len() as u64).await
+	}
+
+	/// appends the data of the given reader as a file with the "unix-like" file permissions 644. The header size must be
+	/// known in advance, so exactly size bytes will be read. A leading "/" will be removed from the filename.
+	pub async fn append_stream<F: Into<String>, R: AsyncRead + Unpin>(&mut self, filename: F, data: R, size: u64) -> Result<()> {
+		let mut header = Header::new_gnu();
+		header.set_size(size);
+		header.set_mode(420);
+		header.set_entry_type(EntryType::file());
+		if let Ok(n) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+			header.set_mtime(n.as_secs())
+		};
+		let filename = filename.into();
+		self.append_data(&mut header, filename.trim_start_matches('/'), data).await
+	}
+
+	/// appends an entry with the given header and path. Long paths will be stored as GNU long name entry.
+	pub async fn append_data<P: AsRef<Path>, R: AsyncRead + Unpin>(&mut self, header: &mut Header, path: P, data: R) -> Result<()> {
+		self.prepare_header_path(header, path.as_ref()).await?;
+		header.set_cksum();
+		let size = match header.entry_size() {
+			Ok(x) => x,
+			Err(e) => return Err(archive_error("append data", e))
+		};
+		self.write_all(header.as_bytes()).await?;
+		let copied = match tokio::io::copy(&mut data.take(size), &mut self.obj).await {
+			Ok(x) => x,
+			Err(e) => return Err(archive_error("append data", e))
+		};
+		if copied != size {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append data; expected {} bytes, got {}", size, copied)))
+		}
+		self.write_padding(size).await
+	}
+
+	/// appends a link (symlink or hardlink, depending on the entry type of the header) to an archive. Long targets will be
+	/// stored as GNU long link entry.
+	/// # Example
+	/// ```
+	/// extern crate tar;
+	/// extern crate tokio;
+	/// extern crate phollaits;
+	///
+	/// use phollaits::*;
+	/// use tar::{EntryType, Header};
+	///
+	/// fn main() -> Result<()> {
+	/// 	let target = format!("{}/target.txt", "very_long_directory_name".repeat(5));
+	/// 	let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+	/// 	let archive = runtime.block_on(async {
+	/// 		let mut b = AsyncTarBuilder::new(Vec::new());
+	/// 		let mut header = Header::new_gnu();
+	/// 		header.set_entry_type(EntryType::Symlink);
+	/// 		b.append_link(&mut header, "link", &target).await?;
+	/// 		b.into_inner().await
+	/// 	})?;
+	///
+	/// 	let mut archive = tar::Archive::new(&archive[..]);
+	/// 	let entry = archive.entries().unwrap().next().unwrap().unwrap();
+	/// 	assert_eq!(entry.link_name().unwrap().unwrap().to_str(), Some(target.as_str()));
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub async fn append_link<P: AsRef<Path>, T: AsRef<Path>>(&mut self, header: &mut Header, path: P, target: T) -> Result<()> {
+		self.prepare_header_link(header, target.as_ref()).await?;
+		header.set_size(0);
+		self.append_data(header, path, tokio::io::empty()).await
+	}
+
+	/// writes the end-of-archive marker and flushes the underlying writer.
+	pub async fn close_archive(self) -> Result<()> {
+		self.into_inner().await.map(|_| ())
+	}
+
+	/// writes the end-of-archive marker, flushes and returns the underlying writer.
+	pub async fn into_inner(mut self) -> Result<W> {
+		self.write_all(&[0; 2 * BLOCK_SIZE as usize]).await?;
+		match self.obj.flush().await {
+			Ok(_) => Ok(self.obj),
+			Err(e) => Err(archive_error("close archive", e))
+		}
+	}
+
+	async fn prepare_header_path(&mut self, header: &mut Header, path: &Path) -> Result<()> {
+		reject_parent_dir(path)?;
+		if header.set_path(path).is_ok() {
+			return Ok(());
+		}
+		let bytes = path.to_string_lossy().replace('\\', "/").into_bytes();
+		let name = &mut header.as_old_mut().name;
+		if bytes.len() < name.len() {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append data; invalid path {}", path.display())))
+		}
+		let truncated_len = name.len();
+		name.copy_from_slice(&bytes[..truncated_len]);
+		self.write_gnu_extension(EntryType::GNULongName, &bytes).await
+	}
+
+	async fn prepare_header_link(&mut self, header: &mut Header, target: &Path) -> Result<()> {
+		reject_parent_dir(target)?;
+		let error = match header.set_link_name(target) {
+			Ok(_) => return Ok(()),
+			Err(e) => e,
+		};
+		let bytes = target.to_string_lossy().replace('\\', "/").into_bytes();
+		let link_name = &mut header.as_old_mut().linkname;
+		if bytes.len() < link_name.len() {
+			return Err(archive_error("append link", error));
+		}
+		let truncated_len = link_name.len();
+		link_name.copy_from_slice(&bytes[..truncated_len]);
+		self.write_gnu_extension(EntryType::GNULongLink, &bytes).await
+	}
+
+	// writes a GNU long name or long link entry, which applies to the next header.
+	async fn write_gnu_extension(&mut self, entry_type: EntryType, bytes: &[u8]) -> Result<()> {
+		let mut extension = Header::new_gnu();
+		extension.as_old_mut().name[..GNU_LONG_LINK.len()].copy_from_slice(GNU_LONG_LINK);
+		extension.set_mode(420);
+		extension.set_mtime(0);
+		extension.set_entry_type(entry_type);
+		extension.set_size(bytes.len() as u64 + 1);
+		extension.set_cksum();
+		self.write_all(extension.as_bytes()).await?;
+		self.write_all(bytes).await?;
+		self.write_all(&[0]).await?;
+		self.write_padding(bytes.len() as u64 + 1).await
+	}
+
+	async fn write_padding(&mut self, size: u64) -> Result<()> {
+		let padding = (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE;
+		self.write_all(&vec![0; padding as usize]).await
+	}
+
+	async fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		match self.obj.write_all(bytes).await {
+			Ok(x) => Ok(x),
+			Err(e) => Err(archive_error("write archive", e))
+		}
+	}
+}
+
+/// An async tar archive reader, which reads from an [AsyncRead]. GNU long names/links and PAX paths are supported.
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate tokio;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use tar::{EntryType, Header};
+///
+/// fn main() {
+/// 	// a PAX header with a malformed record (the length does not cover the record).
+/// 	let data = b"1 abc\n";
+/// 	let mut header = Header::new_ustar();
+/// 	header.set_path("pax").unwrap();
+/// 	header.set_entry_type(EntryType::XHeader);
+/// 	header.set_size(data.len() as u64);
+/// 	header.set_cksum();
+/// 	let mut archive = header.as_bytes().to_vec();
+/// 	archive.extend_from_slice(data);
+/// 	archive.resize(2048, 0);
+///
+/// 	let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// 	runtime.block_on(async {
+/// 		let mut reader = AsyncTarReader::new(&archive[..]);
+/// 		assert!(reader.next_entry().await.is_err());
+/// 	});
+/// }
+/// ```
+pub struct AsyncTarReader<R: AsyncRead + Unpin> {
+	obj: R,
+	remaining: u64,
+	padding: u64,
+	finished: bool,
+}
+
+impl<R: AsyncRead + Unpin> AsyncTarReader<R> {
+	/// creates a new async archive reader.
+	pub fn new(obj: R) -> AsyncTarReader<R> {
+		AsyncTarReader {
+			obj,
+			remaining: 0,
+			padding: 0,
+			finished: false,
+		}
+	}
+
+	/// returns the next entry of the archive or None, if the end of the archive was reached. Unread data of the previous
+	/// entry will be skipped.
+	pub async fn next_entry(&mut self) -> Result<Option<AsyncTarEntry<'_, R>>> {
+		let mut long_name = None;
+		let mut long_link_name = None;
+		let mut pax_path = None;
+		let mut pax_link_path = None;
+		loop {
+			if self.finished {
+				return Ok(None);
+			}
+			let skip = self.remaining + self.padding;
+			self.skip(skip).await?;
+			self.remaining = 0;
+			self.padding = 0;
+			let mut block = [0; BLOCK_SIZE as usize];
+			if !self.read_block(&mut block).await? || block.iter().all(|b| *b == 0) {
+				self.finished = true;
+				return Ok(None);
+			}
+			let mut header = Header::new_old();
+			header.as_mut_bytes().copy_from_slice(&block);
+			verify_checksum(&header)?;
+			let size = match header.entry_size() {
+				Ok(x) => x,
+				Err(e) => return Err(archive_error("read archive", e))
+			};
+			self.remaining = size;
+			self.padding = (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE;
+			match header.entry_type() {
+				EntryType::GNULongName => long_name = Some(self.read_extension().await?),
+				EntryType::GNULongLink => long_link_name = Some(self.read_extension().await?),
+				EntryType::XHeader => {
+					let extension = self.read_extension().await?;
+					for (key, value) in parse_pax_records(&extension)? {
+						match key.as_str() {
+							"path" => pax_path = Some(value),
+							"linkpath" => pax_link_path = Some(value),
+							_ => (),
+						}
+					}
+				},
+				EntryType::XGlobalHeader => { self.read_extension().await?; },
+				_ => {
+					let path = match pax_path.or(long_name) {
+						Some(x) => PathBuf::from(String::from_utf8_lossy(&x).to_string()),
+						None => match header.path() {
+							Ok(x) => x.into_owned(),
+							Err(e) => return Err(archive_error("read archive", e))
+						},
+					};
+					let link_name = match pax_link_path.or(long_link_name) {
+						Some(x) => Some(PathBuf::from(String::from_utf8_lossy(&x).to_string())),
+						None => match header.link_name() {
+							Ok(x) => x.map(|x| x.into_owned()),
+							Err(e) => return Err(archive_error("read archive", e))
+						},
+					};
+					return Ok(Some(AsyncTarEntry { header, path, link_name, archive: self }));
+				},
+			}
+		}
+	}
+
+	// reads the data of an extension entry (e.g. a GNU long name), without a trailing NUL.
+	async fn read_extension(&mut self) -> Result<Vec<u8>> {
+		let size = self.remaining;
+		if size > MAX_EXTENSION_SIZE {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to read archive; extension entry of {} bytes exceeds the limit of {} bytes",
+					size, MAX_EXTENSION_SIZE)));
+		}
+		let mut data = Vec::with_capacity(size as usize);
+		if let Err(e) = (&mut self.obj).take(size).read_to_end(&mut data).await {
+			return Err(archive_error("read archive", e));
+		}
+		if data.len() as u64 != size {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				"Error while trying to read archive; unexpected end of archive"));
+		}
+		self.remaining = 0;
+		while data.last() == Some(&0) {
+			data.pop();
+		}
+		Ok(data)
+	}
+
+	// returns false if the end of the reader was reached before the block.
+	async fn read_block(&mut self, block: &mut [u8]) -> Result<bool> {
+		let mut filled = 0;
+		while filled < block.len() {
+			match self.obj.read(&mut block[filled..]).await {
+				Ok(0) if filled == 0 => return Ok(false),
+				Ok(0) => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::ArchiveError,
+					"Error while trying to read archive; unexpected end of archive")),
+				Ok(n) => filled += n,
+				Err(e) => return Err(archive_error("read archive", e))
+			}
+		}
+		Ok(true)
+	}
+
+	async fn skip(&mut self, count: u64) -> Result<()> {
+		match tokio::io::copy(&mut (&mut self.obj).take(count), &mut tokio::io::sink()).await {
+			Ok(x) if x == count => Ok(()),
+			Ok(_) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				"Error while trying to read archive; unexpected end of archive")),
+			Err(e) => Err(archive_error("read archive", e))
+		}
+	}
+}
+
+/// An entry of an [AsyncTarReader]. The data of the entry can be read by the [AsyncRead] implementation.
+pub struct AsyncTarEntry<'a, R: AsyncRead + Unpin> {
+	header: Header,
+	path: PathBuf,
+	link_name: Option<PathBuf>,
+	archive: &'a mut AsyncTarReader<R>,
+}
+
+impl<R: AsyncRead + Unpin> AsyncTarEntry<'_, R> {
+	/// returns the header of the entry.
+	pub fn header(&self) -> &Header {
+		&self.header
+	}
+
+	/// returns the path of the entry (long names are already resolved).
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// returns the target of a symlink or hardlink entry.
+	pub fn link_name(&self) -> Option<&Path> {
+		self.link_name.as_deref()
+	}
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for AsyncTarEntry<'_, R> {
+	fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+		let this = self.get_mut();
+		if this.archive.remaining == 0 || buf.remaining() == 0 {
+			return Poll::Ready(Ok(()));
+		}
+		let max = cmp::min(buf.remaining() as u64, this.archive.remaining) as usize;
+		let mut limited = ReadBuf::new(buf.initialize_unfilled_to(max));
+		ready!(Pin::new(&mut this.archive.obj).poll_read(cx, &mut limited))?;
+		let count = limited.filled().len();
+		if count == 0 {
+			return Poll::Ready(Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of archive")));
+		}
+		buf.advance(count);
+		this.archive.remaining -= count as u64;
+		Poll::Ready(Ok(()))
+	}
+}
+
+fn verify_checksum(header: &Header) -> Result<()> {
+	let expected = match header.cksum() {
+		Ok(x) => x,
+		Err(e) => return Err(archive_error("read archive", e))
+	};
+	let bytes = header.as_bytes();
+	let checksum = bytes[..148].iter().chain(&[b' '; 8]).chain(&bytes[156..]).map(|b| *b as u32).sum::<u32>();
+	if checksum != expected {
+		return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to read archive; invalid header checksum {} (expected {})", checksum, expected)))
+	}
+	Ok(())
+}
+
+// parses PAX extended header records ("<length> <key>=<value>\n").
+fn parse_pax_records(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+	let mut records = Vec::new();
+	let mut offset = 0;
+	while offset < data.len() {
+		let space = match data[offset..].iter().position(|b| *b == b' ') {
+			Some(x) => offset + x,
+			None => return Err(invalid_pax_record(offset)),
+		};
+		let length: usize = match String::from_utf8_lossy(&data[offset..space]).parse() {
+			Ok(x) => x,
+			Err(_) => return Err(invalid_pax_record(offset)),
+		};
+		// the length includes the length field itself, so the record has to end behind the space.
+		if offset + length <= space || offset + length > data.len() {
+			return Err(invalid_pax_record(offset));
+		}
+		let record = &data[space + 1..offset + length];
+		let record = record.strip_suffix(b"\n").unwrap_or(record);
+		if let Some(equals) = record.iter().position(|b| *b == b'=') {
+			records.push((String::from_utf8_lossy(&record[..equals]).to_string(), record[equals + 1..].to_vec()));
+		}
+		offset += length;
+	}
+	Ok(records)
+}
+
+fn invalid_pax_record(offset: usize) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::ArchiveError,
+		format!("Error while trying to read archive; invalid PAX record at offset {}", offset))
+}
+
+// tar refuses ".." components, so they are rejected before a GNU long name or long link could be used.
+fn reject_parent_dir(path: &Path) -> Result<()> {
+	if path.components().any(|component| component == Component::ParentDir) {
+		return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to append data; invalid path {}", path.display())))
+	}
+	Ok(())
+}
+
+fn archive_error(action: &str, e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(PhollaitsErrorKind::ArchiveError, format!("Error while trying to {}; {}", action, e))
+}
\ No newline at end of file
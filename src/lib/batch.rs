@@ -0,0 +1,179 @@
+/*************************************************************************
+* ph0llux:5d8e2b0f7c49a13e6d0b8f25c7a91e43b06d2f8a9c1e57b04a3f6d92e8c1b750
+*************************************************************************/
+//!batch Module (parallel hashing of many files).
+
+// - STD
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// - internal
+use super::{Digest, HashAlgorithm, HashExt, PhollaitsError, PhollaitsErrorKind, Result};
+
+/// The options for [hash_files] and [hash_directory].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchOptions {
+	/// the algorithms, which will be calculated for every file (default: [HashAlgorithm::Sha256]).
+	pub algorithms: Vec<HashAlgorithm>,
+	/// the number of worker threads (default: the available parallelism of the system).
+	pub threads: usize,
+}
+
+impl Default for BatchOptions {
+	fn default() -> BatchOptions {
+		BatchOptions {
+			algorithms: vec![HashAlgorithm::Sha256],
+			threads: thread::available_parallelism().map(|threads| threads.get()).unwrap_or(1),
+		}
+	}
+}
+
+/// The result of a single file of [hash_files] or [hash_directory].
+#[derive(Debug)]
+pub struct BatchResult {
+	pub path: PathBuf,
+	/// the digests of the file, or the error which occurred while hashing this file.
+	pub digests: Result<BTreeMap<HashAlgorithm, Digest>>,
+}
+
+/// hashes the files in parallel. The results are returned in the order of the given paths; an error of a file will
+/// not abort the other files.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() {
+/// 	let options = BatchOptions { algorithms: vec![HashAlgorithm::Md5, HashAlgorithm::Sha256], threads: 2 };
+/// 	let results = hash_files(&["assets/example.jpg", "assets/missing.bin", "assets/textfile.txt"], &options);
+/// 	assert_eq!(results.len(), 3);
+/// 	let digests = results[0].digests.as_ref().unwrap();
+/// 	assert_eq!(digests[&HashAlgorithm::Md5].to_hex(), "a4494bd1b83303bc0872a996e6c8a8bf");
+/// 	assert!(results[1].digests.is_err());
+/// 	assert!(results[2].digests.is_ok());
+/// }
+/// ```
+pub fn hash_files<P: AsRef<Path> + Sync>(paths: &[P], options: &BatchOptions) -> Vec<BatchResult> {
+	let next = AtomicUsize::new(0);
+	let threads = options.threads.clamp(1, paths.len().max(1));
+	let mut results: Vec<Option<BatchResult>> = (0..paths.len()).map(|_| None).collect();
+	thread::scope(|scope| {
+		let workers: Vec<_> = (0..threads).map(|_| scope.spawn(|| {
+			let mut results = Vec::new();
+			// every worker takes the next file, until all files are hashed.
+			loop {
+				let index = next.fetch_add(1, Ordering::Relaxed);
+				let path = match paths.get(index) {
+					Some(path) => path.as_ref(),
+					None => break,
+				};
+				results.push((index, BatchResult {
+					path: path.to_path_buf(),
+					digests: hash_file(path, &options.algorithms),
+				}));
+			}
+			results
+		})).collect();
+		for worker in workers {
+			let worker_results = match worker.join() {
+				Ok(x) => x,
+				Err(panic) => std::panic::resume_unwind(panic),
+			};
+			for (index, result) in worker_results {
+				results[index] = Some(result);
+			}
+		}
+	});
+	results.into_iter().flatten().collect()
+}
+
+/// hashes all regular files below the directory in parallel (see [hash_files]). Symbolic links are skipped. The results
+/// are sorted by their path. Only an unreadable root directory returns an error: an error while reading a
+/// subdirectory (or one of its entries) is returned as [BatchResult] of the affected path, and the other files will
+/// be hashed anyway.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+/// use std::path::PathBuf;
+///
+/// fn main() -> Result<()> {
+/// 	let results = hash_directory("assets", &BatchOptions::default())?;
+/// 	assert_eq!(results[0].path, PathBuf::from("assets/example.jpg"));
+/// 	assert_eq!(results[1].path, PathBuf::from("assets/textfile.txt"));
+///
+/// 	// file names do not need to be valid UTF-8.
+/// 	#[cfg(unix)]
+/// 	{
+/// 		use std::ffi::OsStr;
+/// 		use std::fs;
+/// 		use std::os::unix::ffi::OsStrExt;
+///
+/// 		let directory = std::env::temp_dir().join(format!("phollaits-batch-{}", std::process::id()));
+/// 		let path = directory.join(OsStr::from_bytes(b"invalid-\xff"));
+/// 		fs::create_dir_all(&directory).unwrap();
+/// 		fs::write(&path, "phollaits").unwrap();
+/// 		let results = hash_directory(&directory, &BatchOptions::default())?;
+/// 		fs::remove_dir_all(&directory).unwrap();
+/// 		assert_eq!(results[0].path, path);
+/// 		assert!(results[0].digests.is_ok());
+/// 	}
+/// 	Ok(())
+/// }
+/// ```
+pub fn hash_directory<P: AsRef<Path>>(root: P, options: &BatchOptions) -> Result<Vec<BatchResult>> {
+	let root = root.as_ref();
+	let entries = fs::read_dir(root).map_err(|e| walk_error(root, e))?;
+	let mut paths = Vec::new();
+	let mut errors = Vec::new();
+	collect_paths(root, entries, &mut paths, &mut errors);
+	paths.sort();
+	let mut results = hash_files(&paths, options);
+	results.append(&mut errors);
+	results.sort_by(|first, second| first.path.cmp(&second.path));
+	Ok(results)
+}
+
+// collects the paths of all regular files below the directory. Errors are collected as results of the affected path.
+fn collect_paths(directory: &Path, entries: fs::ReadDir, paths: &mut Vec<PathBuf>, errors: &mut Vec<BatchResult>) {
+	for entry in entries {
+		let entry = match entry {
+			Ok(x) => x,
+			Err(e) => {
+				errors.push(BatchResult { path: directory.to_path_buf(), digests: Err(walk_error(directory, e)) });
+				continue;
+			},
+		};
+		let path = entry.path();
+		let result = entry.file_type().and_then(|file_type| if file_type.is_dir() {
+			fs::read_dir(&path).map(|entries| collect_paths(&path, entries, paths, errors))
+		} else {
+			if file_type.is_file() {
+				paths.push(path.clone());
+			}
+			Ok(())
+		});
+		if let Err(e) = result {
+			errors.push(BatchResult { digests: Err(walk_error(&path, e)), path });
+		}
+	}
+}
+
+fn walk_error(path: &Path, e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to read {}; {}", path.display(), e))
+}
+
+fn hash_file(path: &Path, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+	match File::open(path) {
+		Ok(mut file) => file.multi_digest(algorithms),
+		Err(e) => Err(PhollaitsError::new(
+			PhollaitsErrorKind::HashingError,
+			format!("Error while trying to open {}; {}", path.display(), e)))
+	}
+}
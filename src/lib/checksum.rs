@@ -0,0 +1,590 @@
+/*************************************************************************
+* ph0llux:7f3c1e9a52d04b68e1c7a93f0d25b84e6c19a07f3d52e8b14c6a09f7e2d35b18
+*************************************************************************/
+//!checksum Module (checksum files like SHA256SUMS, in the GNU coreutils and in the BSD tag format).
+
+// - STD
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// - internal
+use super::{Digest, HashAlgorithm, HashExt, PhollaitsError, PhollaitsErrorKind, Result};
+
+/// The format of a line in a checksum file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChecksumFormat {
+	/// the GNU coreutils format (e.g. of sha256sum): "<hex>  <path>" or "<hex> *<path>" (binary mode).
+	#[default]
+	Gnu,
+	/// the BSD tag format (e.g. of sha256sum --tag): "SHA256 (<path>) = <hex>".
+	Bsd,
+}
+
+/// A single line of a checksum file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumEntry {
+	pub path: PathBuf,
+	pub digest: Digest,
+	/// true, if the line was marked with the binary marker "*" (only used by the GNU format).
+	pub binary: bool,
+	pub format: ChecksumFormat,
+}
+
+impl ChecksumEntry {
+	/// returns the line (without the line break) in the format of the entry. Paths containing a backslash or a line
+	/// break will be escaped like by GNU coreutils. Paths which are not valid UTF-8 will be converted lossy (see
+	/// [ChecksumEntry::to_bytes]).
+	pub fn to_line(&self) -> String {
+		String::from_utf8_lossy(&self.to_bytes()).into_owned()
+	}
+
+	/// returns the line (without the line break) like [ChecksumEntry::to_line], but (on unix) with the raw bytes of the
+	/// path, like GNU coreutils writes it. So paths which are not valid UTF-8 can be found again.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let path = path_bytes(&self.path);
+		let mut line = Vec::new();
+		if path.iter().any(|byte| matches!(byte, b'\\' | b'\n' | b'\r')) {
+			line.push(b'\\');
+		}
+		let mut escaped_path = Vec::with_capacity(path.len());
+		for byte in path {
+			match byte {
+				b'\\' => escaped_path.extend_from_slice(b"\\\\"),
+				b'\n' => escaped_path.extend_from_slice(b"\\n"),
+				b'\r' => escaped_path.extend_from_slice(b"\\r"),
+				_ => escaped_path.push(byte),
+			}
+		}
+		match self.format {
+			ChecksumFormat::Gnu => {
+				line.extend_from_slice(self.digest.to_hex().as_bytes());
+				line.extend_from_slice(if self.binary { b" *" } else { b"  " });
+				line.extend_from_slice(&escaped_path);
+			},
+			ChecksumFormat::Bsd => {
+				line.extend_from_slice(bsd_tag(self.digest.algorithm()).as_bytes());
+				line.extend_from_slice(b" (");
+				line.extend_from_slice(&escaped_path);
+				line.extend_from_slice(b") = ");
+				line.extend_from_slice(self.digest.to_hex().as_bytes());
+			},
+		}
+		line
+	}
+}
+
+/// The result of the verification of a single file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumStatus {
+	/// the digest of the file matches.
+	Ok,
+	/// the digest of the file does not match (or the file could not be read).
+	Failed,
+	/// the file does not exist.
+	Missing,
+}
+
+/// The verification result of a single [ChecksumEntry].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumResult {
+	/// the path, as listed in the checksum file.
+	pub path: PathBuf,
+	pub status: ChecksumStatus,
+	pub expected: Digest,
+	/// the digest of the file, if it could be read.
+	pub actual: Option<Digest>,
+}
+
+/// A parsed checksum file. Both formats can be mixed in one file; empty lines are ignored.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let checksums = concat!(
+/// 		"5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793  example.jpg\n",
+/// 		"SHA256 (textfile.txt) = 15e2b0d3c33891ebb0f1ef609ec419420c20e320ce94c65fbc8c3312448eb225\n",
+/// 		"0000000000000000000000000000000000000000000000000000000000000000 *missing.bin\n",
+/// 		"this line is improperly formatted\n");
+/// 	let file = ChecksumFile::parse(checksums, HashAlgorithm::Sha256, false)?;
+/// 	assert_eq!(file.entries.len(), 3);
+/// 	assert_eq!(file.improperly_formatted, vec![4]);
+/// 	assert!(ChecksumFile::parse(checksums, HashAlgorithm::Sha256, true).is_err());
+///
+/// 	let results = file.verify("assets");
+/// 	assert_eq!(results[0].status, ChecksumStatus::Ok);
+/// 	assert_eq!(results[1].status, ChecksumStatus::Ok);
+/// 	assert_eq!(results[2].status, ChecksumStatus::Missing);
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChecksumFile {
+	pub entries: Vec<ChecksumEntry>,
+	/// the (1-based) line numbers of the improperly formatted lines.
+	pub improperly_formatted: Vec<usize>,
+}
+
+impl ChecksumFile {
+	/// parses the content of a checksum file. The algorithm is used for the lines in the GNU format (the BSD format
+	/// contains the algorithm). In strict mode, an improperly formatted line results in an error.
+	pub fn parse(text: &str, algorithm: HashAlgorithm, strict: bool) -> Result<ChecksumFile> {
+		ChecksumFile::parse_bytes(text.as_bytes(), algorithm, strict)
+	}
+
+	/// parses the raw content of a checksum file (see [ChecksumFile::parse]). On unix, the paths do not need to be valid
+	/// UTF-8.
+	pub fn parse_bytes(bytes: &[u8], algorithm: HashAlgorithm, strict: bool) -> Result<ChecksumFile> {
+		let mut file = ChecksumFile::default();
+		for (index, line) in bytes.split(|byte| *byte == b'\n').enumerate() {
+			let line = line.strip_suffix(b"\r").unwrap_or(line);
+			if line.iter().all(|byte| byte.is_ascii_whitespace()) {
+				continue;
+			}
+			match parse_bsd_line(line).or_else(|| parse_gnu_line(line, algorithm)) {
+				Some(entry) => file.entries.push(entry),
+				None if strict => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::HashingError,
+					format!("Improperly formatted checksum line {}: {}", index + 1, String::from_utf8_lossy(line)))),
+				None => file.improperly_formatted.push(index + 1),
+			}
+		}
+		Ok(file)
+	}
+
+	/// reads and parses the checksum file at the given path (see [ChecksumFile::parse_bytes]).
+	pub fn read<P: AsRef<Path>>(path: P, algorithm: HashAlgorithm, strict: bool) -> Result<ChecksumFile> {
+		let path = path.as_ref();
+		match fs::read(path) {
+			Ok(bytes) => ChecksumFile::parse_bytes(&bytes, algorithm, strict),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to read checksum file {}; {}", path.display(), e)))
+		}
+	}
+
+	/// verifies all entries. Relative paths are resolved against the given base directory.
+	pub fn verify<P: AsRef<Path>>(&self, base_directory: P) -> Vec<ChecksumResult> {
+		let base_directory = base_directory.as_ref();
+		self.entries.iter().map(|entry| verify_entry(entry, base_directory)).collect()
+	}
+
+	/// writes all entries to the given path (see [ChecksumEntry::to_bytes]).
+	pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+		let path = path.as_ref();
+		let mut bytes = Vec::new();
+		for entry in &self.entries {
+			bytes.extend_from_slice(&entry.to_bytes());
+			bytes.push(b'\n');
+		}
+		match fs::write(path, bytes) {
+			Ok(_) => Ok(()),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to write checksum file {}; {}", path.display(), e)))
+		}
+	}
+}
+
+impl fmt::Display for ChecksumFile {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for entry in &self.entries {
+			writeln!(f, "{}", entry.to_line())?;
+		}
+		Ok(())
+	}
+}
+
+/// The options for [generate_checksums].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumOptions {
+	/// the format of the lines (default: [ChecksumFormat::Gnu]).
+	pub format: ChecksumFormat,
+	/// sets the binary marker "*" in the GNU format (default: false).
+	pub binary: bool,
+	/// the paths are relative to the root directory. Otherwise the root directory will be prepended (default: true).
+	pub relative_paths: bool,
+	/// only files matching at least one of these patterns will be listed. If empty, all files will be listed (default:
+	/// empty).
+	pub include: Vec<String>,
+	/// files and directories matching one of these patterns will be skipped (default: empty).
+	pub exclude: Vec<String>,
+	/// symbolic links to files will be hashed. Otherwise they will be skipped. Symbolic links to directories will never
+	/// be followed (default: false).
+	pub follow_symlinks: bool,
+}
+
+impl Default for ChecksumOptions {
+	fn default() -> ChecksumOptions {
+		ChecksumOptions {
+			format: ChecksumFormat::default(),
+			binary: false,
+			relative_paths: true,
+			include: Vec::new(),
+			exclude: Vec::new(),
+			follow_symlinks: false,
+		}
+	}
+}
+
+/// walks the directory tree and hashes all regular files. The entries are sorted by their relative path (with "/" as
+/// separator, compared bytewise), so the output does not depend on the platform or the order of the file system.
+///
+/// The include and exclude patterns are matched against the relative path with "/" as separator, or against the file
+/// name if the pattern contains no "/". "*" matches any characters except "/", "**" matches any characters and "?"
+/// matches a single character except "/". "**/" at the start of a pattern or after a "/" also matches no directory at
+/// all (e.g. "**/*.txt" matches "a.txt" and "docs/a.txt").
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let options = ChecksumOptions { exclude: vec!["*.txt".to_string()], ..Default::default() };
+/// 	let file = generate_checksums("assets", HashAlgorithm::Sha256, &options)?;
+/// 	assert_eq!(file.to_string(), "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793  example.jpg\n");
+///
+/// 	let options = ChecksumOptions { format: ChecksumFormat::Bsd, ..Default::default() };
+/// 	let file = generate_checksums("assets", HashAlgorithm::Md5, &options)?;
+/// 	assert_eq!(file.entries[1].to_line(), "MD5 (textfile.txt) = 25f9e794323b453885f5181f1b624d0b");
+///
+/// 	// the generated checksums can be verified again.
+/// 	let parsed = ChecksumFile::parse(&file.to_string(), HashAlgorithm::Md5, true)?;
+/// 	assert!(parsed.verify("assets").iter().all(|result| result.status == ChecksumStatus::Ok));
+///
+/// 	// file names, which are not valid UTF-8, are written as raw bytes (like by GNU coreutils).
+/// 	#[cfg(unix)]
+/// 	{
+/// 		use std::ffi::OsStr;
+/// 		use std::fs;
+/// 		use std::os::unix::ffi::OsStrExt;
+///
+/// 		let directory = std::env::temp_dir().join(format!("phollaits-checksums-{}", std::process::id()));
+/// 		fs::create_dir_all(&directory).unwrap();
+/// 		fs::write(directory.join(OsStr::from_bytes(b"invalid-\xff")), "phollaits").unwrap();
+/// 		fs::write(directory.join("a".repeat(64)), "phollaits").unwrap();
+/// 		// many wildcards do not result in exponential backtracking.
+/// 		let options = ChecksumOptions { exclude: vec!["a*a*a*a*a*a*a*a*a*a*a*a*b".to_string()], ..Default::default() };
+/// 		let file = generate_checksums(&directory, HashAlgorithm::Sha256, &options)?;
+/// 		assert!(file.entries[1].to_bytes().ends_with(b"  invalid-\xff"));
+/// 		file.write(directory.join("SHA256SUMS"))?;
+/// 		let results = verify_checksum_file(directory.join("SHA256SUMS"), HashAlgorithm::Sha256, true)?;
+/// 		fs::remove_dir_all(&directory).unwrap();
+/// 		assert_eq!(results.len(), 2);
+/// 		assert!(results.iter().all(|result| result.status == ChecksumStatus::Ok));
+/// 	}
+/// 	Ok(())
+/// }
+/// ```
+pub fn generate_checksums<P: AsRef<Path>>(root: P, algorithm: HashAlgorithm, options: &ChecksumOptions) -> Result<ChecksumFile> {
+	let root = root.as_ref();
+	let mut files = Vec::new();
+	collect_files(root, Path::new(""), options, &mut files)?;
+	files.sort_by_cached_key(|relative_path| sort_key(relative_path));
+
+	let mut checksum_file = ChecksumFile::default();
+	for relative_path in files {
+		let path = root.join(&relative_path);
+		let digest = match fs::File::open(&path) {
+			Ok(mut file) => file.digest_with(algorithm)?,
+			Err(e) => return Err(generate_error(&path, e)),
+		};
+		checksum_file.entries.push(ChecksumEntry {
+			path: if options.relative_paths { relative_path } else { path },
+			digest,
+			binary: options.binary && options.format == ChecksumFormat::Gnu,
+			format: options.format,
+		});
+	}
+	Ok(checksum_file)
+}
+
+// collects the relative paths of all files below the directory.
+fn collect_files(root: &Path, relative_directory: &Path, options: &ChecksumOptions, files: &mut Vec<PathBuf>) -> Result<()> {
+	let directory = root.join(relative_directory);
+	let entries = fs::read_dir(&directory).map_err(|e| generate_error(&directory, e))?;
+	for entry in entries {
+		let entry = entry.map_err(|e| generate_error(&directory, e))?;
+		let file_name = entry.file_name();
+		let relative_path = relative_directory.join(&file_name);
+		// the patterns are matched against the (lossy converted) path with "/" as separator.
+		let name = file_name.to_string_lossy();
+		let pattern_path = relative_path.components()
+			.map(|component| component.as_os_str().to_string_lossy())
+			.collect::<Vec<_>>()
+			.join("/");
+		if options.exclude.iter().any(|pattern| pattern_matches(pattern, &pattern_path, &name)) {
+			continue;
+		}
+		let mut file_type = entry.file_type().map_err(|e| generate_error(&entry.path(), e))?;
+		if file_type.is_symlink() {
+			if !options.follow_symlinks {
+				continue;
+			}
+			file_type = match fs::metadata(entry.path()) {
+				Ok(metadata) if metadata.is_file() => metadata.file_type(),
+				// dangling symlinks and symlinks to directories.
+				_ => continue,
+			};
+		}
+		if file_type.is_dir() {
+			collect_files(root, &relative_path, options, files)?;
+		} else if file_type.is_file()
+			&& (options.include.is_empty() || options.include.iter().any(|pattern| pattern_matches(pattern, &pattern_path, &name))) {
+			files.push(relative_path);
+		}
+	}
+	Ok(())
+}
+
+// the relative path with "/" as separator, which is used to sort the entries.
+fn sort_key(relative_path: &Path) -> Vec<u8> {
+	relative_path.components()
+		.map(|component| path_bytes(Path::new(component.as_os_str())))
+		.collect::<Vec<_>>()
+		.join(&b'/')
+}
+
+fn pattern_matches(pattern: &str, relative_path: &str, name: &str) -> bool {
+	let pattern: Vec<char> = pattern.chars().collect();
+	let text: Vec<char> = if pattern.contains(&'/') { relative_path.chars().collect() } else { name.chars().collect() };
+	glob_matches(&pattern, &text)
+}
+
+// matches iteratively: on a mismatch, the last "*" takes one more character. If it can not (at a "/"), the last "**"
+// takes more characters instead, so there is no exponential backtracking.
+fn glob_matches(pattern: &[char], text: &[char]) -> bool {
+	let (mut p, mut t) = (0, 0);
+	// the pattern index after the last "*" and the text index, where it stopped matching.
+	let mut single: Option<(usize, usize)> = None;
+	// the same for the last "**" (and whether it is followed by a "/", so it only stops behind a "/").
+	let mut double: Option<(usize, usize, bool)> = None;
+	loop {
+		if p < pattern.len() {
+			match pattern[p] {
+				'*' if pattern.get(p + 1) == Some(&'*') => {
+					// "**/" (at the start or after a "/") also matches no directory at all.
+					let slash = pattern.get(p + 2) == Some(&'/') && (p == 0 || pattern[p - 1] == '/');
+					p += if slash { 3 } else { 2 };
+					double = Some((p, t, slash));
+					single = None;
+					continue;
+				},
+				'*' => {
+					p += 1;
+					single = Some((p, t));
+					continue;
+				},
+				'?' if t < text.len() && text[t] != '/' => {
+					p += 1;
+					t += 1;
+					continue;
+				},
+				c if c != '?' && text.get(t) == Some(&c) => {
+					p += 1;
+					t += 1;
+					continue;
+				},
+				_ => (),
+			}
+		} else if t == text.len() {
+			return true;
+		}
+		if let Some((single_p, single_t)) = single {
+			if single_t < text.len() && text[single_t] != '/' {
+				single = Some((single_p, single_t + 1));
+				p = single_p;
+				t = single_t + 1;
+				continue;
+			}
+		}
+		let (double_p, double_t, slash) = match double {
+			Some(x) => x,
+			None => return false,
+		};
+		let next = if slash {
+			text[double_t..].iter().position(|c| *c == '/').map(|index| double_t + index + 1)
+		} else if double_t < text.len() {
+			Some(double_t + 1)
+		} else {
+			None
+		};
+		match next {
+			Some(next) => {
+				double = Some((double_p, next, slash));
+				single = None;
+				p = double_p;
+				t = next;
+			},
+			None => return false,
+		}
+	}
+}
+
+// the tag of the BSD format, as written by the tools of GNU coreutils.
+fn bsd_tag(algorithm: HashAlgorithm) -> String {
+	match algorithm {
+		#[cfg(feature = "blake2")]
+		HashAlgorithm::Blake2b => "BLAKE2b".to_string(),
+		#[cfg(feature = "blake2")]
+		HashAlgorithm::Blake2s => "BLAKE2s".to_string(),
+		_ => algorithm.name().to_uppercase(),
+	}
+}
+
+fn generate_error(path: &Path, e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to generate checksums for {}; {}", path.display(), e))
+}
+
+/// reads the checksum file and verifies all listed files (relative to the directory of the checksum file, see
+/// [ChecksumFile]).
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+/// 	let path = std::env::temp_dir().join("phollaits_SHA256SUMS");
+/// 	fs::write(&path, "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793  example.jpg\n").unwrap();
+/// 	let results = verify_checksum_file(&path, HashAlgorithm::Sha256, true)?;
+/// 	// the example.jpg is not in the temp directory.
+/// 	assert_eq!(results[0].status, ChecksumStatus::Missing);
+/// 	fs::remove_file(&path).unwrap();
+/// 	Ok(())
+/// }
+/// ```
+pub fn verify_checksum_file<P: AsRef<Path>>(path: P, algorithm: HashAlgorithm, strict: bool) -> Result<Vec<ChecksumResult>> {
+	let path = path.as_ref();
+	let file = ChecksumFile::read(path, algorithm, strict)?;
+	let base_directory = path.parent().unwrap_or_else(|| Path::new(""));
+	Ok(file.verify(base_directory))
+}
+
+fn verify_entry(entry: &ChecksumEntry, base_directory: &Path) -> ChecksumResult {
+	let mut result = ChecksumResult {
+		path: entry.path.clone(),
+		status: ChecksumStatus::Failed,
+		expected: entry.digest.clone(),
+		actual: None,
+	};
+	let mut file = match fs::File::open(base_directory.join(&entry.path)) {
+		Ok(x) => x,
+		Err(e) => {
+			if e.kind() == io::ErrorKind::NotFound {
+				result.status = ChecksumStatus::Missing;
+			}
+			return result;
+		}
+	};
+	if let Ok(actual) = file.digest_with(entry.digest.algorithm()) {
+		if actual == entry.digest {
+			result.status = ChecksumStatus::Ok;
+		}
+		result.actual = Some(actual);
+	}
+	result
+}
+
+// "<hex>  <path>" or "<hex> *<path>". If the path contains a backslash or a newline, the line starts with a backslash
+// and the path is escaped.
+fn parse_gnu_line(line: &[u8], algorithm: HashAlgorithm) -> Option<ChecksumEntry> {
+	let (escaped, line) = match line.strip_prefix(b"\\") {
+		Some(line) => (true, line),
+		None => (false, line),
+	};
+	let hex_len = algorithm.digest_len() * 2;
+	let hex = line.get(..hex_len)?;
+	let rest = line.get(hex_len..)?;
+	let (binary, path) = if let Some(path) = rest.strip_prefix(b" *") {
+		(true, path)
+	} else {
+		(false, rest.strip_prefix(b"  ")?)
+	};
+	Some(ChecksumEntry {
+		path: parse_path(path, escaped)?,
+		digest: parse_hex(algorithm, hex)?,
+		binary,
+		format: ChecksumFormat::Gnu,
+	})
+}
+
+// "<ALGORITHM> (<path>) = <hex>", with the same escaping as the GNU format.
+fn parse_bsd_line(line: &[u8]) -> Option<ChecksumEntry> {
+	let (escaped, line) = match line.strip_prefix(b"\\") {
+		Some(line) => (true, line),
+		None => (false, line),
+	};
+	let separator = b" (";
+	let index = line.windows(separator.len()).position(|window| window == separator)?;
+	let (tag, rest) = (&line[..index], &line[index + separator.len()..]);
+	let separator = b") = ";
+	let index = rest.windows(separator.len()).rposition(|window| window == separator)?;
+	let (path, hex) = (&rest[..index], &rest[index + separator.len()..]);
+	let algorithm: HashAlgorithm = std::str::from_utf8(tag).ok()?.parse().ok()?;
+	Some(ChecksumEntry {
+		path: parse_path(path, escaped)?,
+		digest: parse_hex(algorithm, hex)?,
+		binary: false,
+		format: ChecksumFormat::Bsd,
+	})
+}
+
+fn parse_hex(algorithm: HashAlgorithm, hex: &[u8]) -> Option<Digest> {
+	// the parsing of the digest would also accept surrounding whitespace.
+	if hex.len() != algorithm.digest_len() * 2 || !hex.iter().all(|byte| byte.is_ascii_hexdigit()) {
+		return None;
+	}
+	Digest::from_hex(algorithm, std::str::from_utf8(hex).ok()?).ok()
+}
+
+fn parse_path(path: &[u8], escaped: bool) -> Option<PathBuf> {
+	if path.is_empty() {
+		return None;
+	}
+	if !escaped {
+		return path_from_bytes(path.to_vec());
+	}
+	let mut unescaped = Vec::with_capacity(path.len());
+	let mut bytes = path.iter();
+	while let Some(byte) = bytes.next() {
+		if *byte != b'\\' {
+			unescaped.push(*byte);
+			continue;
+		}
+		match bytes.next()? {
+			b'\\' => unescaped.push(b'\\'),
+			b'n' => unescaped.push(b'\n'),
+			b'r' => unescaped.push(b'\r'),
+			_ => return None,
+		}
+	}
+	path_from_bytes(unescaped)
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+	use std::os::unix::ffi::OsStrExt;
+	path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+	path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+	use std::os::unix::ffi::OsStringExt;
+	Some(PathBuf::from(std::ffi::OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> Option<PathBuf> {
+	String::from_utf8(bytes).ok().map(PathBuf::from)
+}
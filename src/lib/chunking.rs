@@ -0,0 +1,187 @@
+/*************************************************************************
+* ph0llux:c07e4a9b3d15f82e6a0c9d47b1e38f52a6d90b1c4e7f23a85d06b9c1e4f7a230
+*************************************************************************/
+//!chunking Module (content defined chunking with FastCDC).
+
+// - STD
+use std::io;
+
+// - internal
+use super::{Digest, HashAlgorithm, HashDataExt, PhollaitsError, PhollaitsErrorKind, Result};
+
+// the gear table, generated with splitmix64, so the chunk boundaries are the same on all platforms and versions.
+const GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+	let mut table = [0u64; 256];
+	let mut state: u64 = 0x7068_6f6c_6c61_6974;
+	let mut index = 0;
+	while index < 256 {
+		state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut value = state;
+		value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		table[index] = value ^ (value >> 31);
+		index += 1;
+	}
+	table
+}
+
+/// The options for a [Chunker].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkerOptions {
+	/// the minimum size of a chunk in bytes; only the last chunk can be smaller (default: 2 KiB).
+	pub min_size: usize,
+	/// the average size of a chunk in bytes (default: 8 KiB).
+	pub avg_size: usize,
+	/// the maximum size of a chunk in bytes (default: 64 KiB).
+	pub max_size: usize,
+	/// the algorithm of the chunk digests (default: [HashAlgorithm::Sha256]).
+	pub algorithm: HashAlgorithm,
+}
+
+impl Default for ChunkerOptions {
+	fn default() -> ChunkerOptions {
+		ChunkerOptions {
+			min_size: 2 * 1024,
+			avg_size: 8 * 1024,
+			max_size: 64 * 1024,
+			algorithm: HashAlgorithm::Sha256,
+		}
+	}
+}
+
+/// A content defined chunk of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+	/// the offset of the chunk in the input.
+	pub offset: u64,
+	pub length: usize,
+	pub digest: Digest,
+}
+
+/// An iterator, which splits the input into content defined chunks using FastCDC (with normalized chunking). The
+/// boundaries depend only on the content around them, so inserting or removing bytes only changes the chunks near the
+/// modification. The input will be read lazily; at most [ChunkerOptions::max_size] bytes are kept in memory.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+/// use std::collections::HashSet;
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+/// 	let options = ChunkerOptions { min_size: 1024, avg_size: 4096, max_size: 16384, ..Default::default() };
+/// 	let data = fs::read("assets/example.jpg").unwrap();
+/// 	let chunks = Chunker::new(&data[..], options.clone())?.collect::<Result<Vec<Chunk>>>()?;
+/// 	assert_eq!(chunks[0].offset, 0);
+/// 	assert_eq!(chunks.iter().map(|chunk| chunk.length).sum::<usize>(), data.len());
+/// 	assert!(chunks.iter().all(|chunk| chunk.length <= 16384));
+///
+/// 	// bytes inserted at the beginning only change the first chunk.
+/// 	let mut modified = b"inserted".to_vec();
+/// 	modified.extend_from_slice(&data);
+/// 	let digests: HashSet<Digest> = chunks.into_iter().map(|chunk| chunk.digest).collect();
+/// 	let modified_chunks = Chunker::new(&modified[..], options)?.collect::<Result<Vec<Chunk>>>()?;
+/// 	assert!(modified_chunks[1..].iter().all(|chunk| digests.contains(&chunk.digest)));
+/// 	Ok(())
+/// }
+/// ```
+pub struct Chunker<R: io::Read> {
+	reader: R,
+	options: ChunkerOptions,
+	// the mask for chunks smaller than the average size (harder to match) and for larger chunks (easier to match).
+	mask_small: u64,
+	mask_large: u64,
+	buffer: Vec<u8>,
+	offset: u64,
+	eof: bool,
+}
+
+impl<R: io::Read> Chunker<R> {
+	/// returns a new chunker for the reader. The sizes must satisfy 0 < min_size <= avg_size <= max_size.
+	pub fn new(reader: R, options: ChunkerOptions) -> Result<Chunker<R>> {
+		if options.min_size == 0 || options.min_size > options.avg_size || options.avg_size > options.max_size {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to create chunker; invalid chunk sizes {}/{}/{}",
+					options.min_size, options.avg_size, options.max_size)));
+		}
+		let bits = usize::BITS - 1 - options.avg_size.leading_zeros();
+		Ok(Chunker {
+			reader,
+			mask_small: mask(bits + 2),
+			mask_large: mask(bits.saturating_sub(2).max(1)),
+			buffer: Vec::with_capacity(options.max_size),
+			options,
+			offset: 0,
+			eof: false,
+		})
+	}
+
+	// fills the buffer up to the maximum chunk size.
+	fn fill_buffer(&mut self) -> Result<()> {
+		let mut buffer = [0; 8 * 1024];
+		while !self.eof && self.buffer.len() < self.options.max_size {
+			let wanted = (self.options.max_size - self.buffer.len()).min(buffer.len());
+			match self.reader.read(&mut buffer[..wanted]) {
+				Ok(0) => self.eof = true,
+				Ok(count) => self.buffer.extend_from_slice(&buffer[..count]),
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+				Err(e) => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::HashingError,
+					format!("Error while trying to read input for chunking; {}", e)))
+			}
+		}
+		Ok(())
+	}
+
+	// returns the length of the next chunk in the buffer.
+	fn cut_point(&self) -> usize {
+		let data = &self.buffer[..];
+		if data.len() <= self.options.min_size {
+			return data.len();
+		}
+		let normal_size = self.options.avg_size.min(data.len());
+		let mut hash = 0u64;
+		// the bytes before the minimum size are skipped.
+		for (index, byte) in data.iter().enumerate().skip(self.options.min_size) {
+			hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+			let mask = if index < normal_size { self.mask_small } else { self.mask_large };
+			if hash & mask == 0 {
+				return index + 1;
+			}
+		}
+		data.len()
+	}
+}
+
+impl<R: io::Read> Iterator for Chunker<R> {
+	type Item = Result<Chunk>;
+
+	fn next(&mut self) -> Option<Result<Chunk>> {
+		if let Err(e) = self.fill_buffer() {
+			// the error is returned only once.
+			self.eof = true;
+			self.buffer.clear();
+			return Some(Err(e));
+		}
+		if self.buffer.is_empty() {
+			return None;
+		}
+		let length = self.cut_point();
+		let digest = match self.buffer[..length].digest_with(self.options.algorithm) {
+			Ok(x) => x,
+			Err(e) => return Some(Err(e)),
+		};
+		let chunk = Chunk { offset: self.offset, length, digest };
+		self.buffer.drain(..length);
+		self.offset += length as u64;
+		Some(Ok(chunk))
+	}
+}
+
+// a mask with the given number of the highest bits set, as the high bits of the gear hash depend on more bytes.
+fn mask(bits: u32) -> u64 {
+	!0u64 << (64 - bits.min(64))
+}
@@ -0,0 +1,373 @@
+/*************************************************************************
+* ph0llux:2c7e94b0d15a3f68e9c42d7a1b0f35e86d2a4c19f7b3e05d68a1c92b4e7f0d13
+*************************************************************************/
+//!compression Module (pigz-like parallel compression).
+
+// - STD
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// - external
+#[cfg(feature = "gzip")]
+use flate2::{Compress, Compression, FlushCompress};
+
+#[cfg(feature = "gzip")]
+const GZIP_HEADER: [u8; 10] = [0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff];
+// an empty, fixed huffman coded deflate block with the BFINAL bit set.
+#[cfg(feature = "gzip")]
+const DEFLATE_FINAL_BLOCK: [u8; 2] = [0x03, 0x00];
+
+/// The output format of a [ParallelCompressor].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionFormat {
+	/// a single gzip member (the blocks are compressed independently as raw deflate blocks).
+	#[cfg(feature = "gzip")]
+	Gzip,
+	/// a zstd stream (each block is compressed as an independent zstd frame).
+	#[cfg(feature = "zstd")]
+	Zstd,
+}
+
+impl CompressionFormat {
+	fn default_level(&self) -> i32 {
+		match self {
+			#[cfg(feature = "gzip")]
+			CompressionFormat::Gzip => 6,
+			#[cfg(feature = "zstd")]
+			CompressionFormat::Zstd => 3,
+		}
+	}
+
+	// zstd uses a much larger window than deflate (32 KiB), so the blocks have to be larger to keep the ratio.
+	fn default_block_size(&self) -> usize {
+		match self {
+			#[cfg(feature = "gzip")]
+			CompressionFormat::Gzip => 128 * 1024,
+			#[cfg(feature = "zstd")]
+			CompressionFormat::Zstd => 4 * 1024 * 1024,
+		}
+	}
+}
+
+struct CompressedBlock {
+	sequence_number: u64,
+	data: io::Result<Vec<u8>>,
+	#[cfg(feature = "gzip")]
+	crc: crc32fast::Hasher,
+}
+
+/// A writer, which splits the stream into blocks, compresses the blocks on a thread pool and writes the compressed
+/// blocks in order to the underlying writer. The output is a standard gzip or zstd stream.
+/// The stream will be finished when the compressor is dropped - use [ParallelCompressor::finish] to handle errors.
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate flate2;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use tar::{Archive, Builder};
+/// use flate2::read::GzDecoder;
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+///		let file = fs::File::create("/tmp/archive.tar.gz").unwrap();
+/// 	let compressor = ParallelCompressor::new(file, CompressionFormat::Gzip).workers(4).block_size(16 * 1024);
+/// 	let mut b = Builder::new(compressor);
+/// 	b.append_file_directly("assets/example.jpg")?;
+/// 	b.append_text("example01.txt", "this is an example text")?;
+/// 	b.into_inner().unwrap().finish().unwrap();
+///
+/// 	let mut archive = Archive::new(GzDecoder::new(fs::File::open("/tmp/archive.tar.gz").unwrap()));
+/// 	let mut entry = archive.entries().unwrap().next().unwrap().unwrap();
+/// 	assert_eq!(entry.sha256sum()?, "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793");
+/// 	Ok(())
+/// }
+/// ```
+pub struct ParallelCompressor<W: io::Write> {
+	obj: Option<W>,
+	format: CompressionFormat,
+	level: i32,
+	block_size: usize,
+	workers: usize,
+	buffer: Vec<u8>,
+	job_sender: Option<Sender<(u64, Vec<u8>)>>,
+	result_receiver: Option<Receiver<CompressedBlock>>,
+	threads: Vec<thread::JoinHandle<()>>,
+	next_sequence_number: u64,
+	next_to_write: u64,
+	finished_blocks: BTreeMap<u64, CompressedBlock>,
+	header_written: bool,
+	finished: bool,
+	total_in: u64,
+	#[cfg(feature = "gzip")]
+	crc: crc32fast::Hasher,
+}
+
+impl<W: io::Write> ParallelCompressor<W> {
+	/// creates a new parallel compressor with the default compression level, the default block size (128 KiB for gzip,
+	/// 4 MiB for zstd) and one worker per available cpu.
+	pub fn new(obj: W, format: CompressionFormat) -> ParallelCompressor<W> {
+		ParallelCompressor {
+			obj: Some(obj),
+			format,
+			level: format.default_level(),
+			block_size: format.default_block_size(),
+			workers: thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+			buffer: Vec::new(),
+			job_sender: None,
+			result_receiver: None,
+			threads: Vec::new(),
+			next_sequence_number: 0,
+			next_to_write: 0,
+			finished_blocks: BTreeMap::new(),
+			header_written: false,
+			finished: false,
+			total_in: 0,
+			#[cfg(feature = "gzip")]
+			crc: crc32fast::Hasher::new(),
+		}
+	}
+
+	/// sets the number of worker threads (at least one).
+	pub fn workers(mut self, workers: usize) -> ParallelCompressor<W> {
+		self.workers = workers.max(1);
+		self
+	}
+
+	/// sets the size of the (uncompressed) blocks, which will be compressed independently (at least 1 KiB).
+	pub fn block_size(mut self, block_size: usize) -> ParallelCompressor<W> {
+		self.block_size = block_size.max(1024);
+		self
+	}
+
+	/// sets the compression level (0-9 for gzip, 1-22 for zstd).
+	pub fn level(mut self, level: i32) -> ParallelCompressor<W> {
+		self.level = level;
+		self
+	}
+
+	/// returns a reference to the underlying writer.
+	pub fn get_ref(&self) -> Option<&W> {
+		self.obj.as_ref()
+	}
+
+	/// compresses the remaining data, writes the end of the stream and returns the underlying writer.
+	pub fn finish(mut self) -> io::Result<W> {
+		self.try_finish()?;
+		match self.obj.take() {
+			Some(x) => Ok(x),
+			None => Err(io::Error::other("compressor is already finished")),
+		}
+	}
+
+	fn try_finish(&mut self) -> io::Result<()> {
+		if self.finished || self.obj.is_none() {
+			return Ok(());
+		}
+		if !self.buffer.is_empty() || self.next_sequence_number == 0 {
+			let block = std::mem::take(&mut self.buffer);
+			self.submit(block)?;
+		}
+		self.job_sender = None;
+		while self.next_to_write < self.next_sequence_number {
+			self.receive_block()?;
+		}
+		for thread in self.threads.drain(..) {
+			let _ = thread.join();
+		}
+		self.write_trailer()?;
+		self.finished = true;
+		match &mut self.obj {
+			Some(x) => x.flush(),
+			None => Ok(()),
+		}
+	}
+
+	fn start_workers(&mut self) {
+		let (job_sender, job_receiver) = channel::<(u64, Vec<u8>)>();
+		let (result_sender, result_receiver) = channel();
+		let job_receiver = Arc::new(Mutex::new(job_receiver));
+		for _ in 0..self.workers {
+			let job_receiver = Arc::clone(&job_receiver);
+			let result_sender = result_sender.clone();
+			let format = self.format;
+			let level = self.level;
+			self.threads.push(thread::spawn(move || loop {
+				let job = match job_receiver.lock() {
+					Ok(receiver) => receiver.recv(),
+					Err(_) => return,
+				};
+				let (sequence_number, data) = match job {
+					Ok(x) => x,
+					Err(_) => return,
+				};
+				if result_sender.send(compress_block(format, level, sequence_number, &data)).is_err() {
+					return;
+				}
+			}));
+		}
+		self.job_sender = Some(job_sender);
+		self.result_receiver = Some(result_receiver);
+	}
+
+	fn submit(&mut self, block: Vec<u8>) -> io::Result<()> {
+		if self.job_sender.is_none() {
+			self.start_workers();
+		}
+		// limits the number of blocks in memory.
+		while self.next_sequence_number - self.next_to_write >= 2 * self.workers as u64 {
+			self.receive_block()?;
+		}
+		let sent = match &self.job_sender {
+			Some(sender) => sender.send((self.next_sequence_number, block)).is_ok(),
+			None => false,
+		};
+		if !sent {
+			return Err(io::Error::other("compression worker threads stopped unexpectedly"));
+		}
+		self.next_sequence_number += 1;
+		Ok(())
+	}
+
+	// receives a compressed block and writes all blocks which are ready in order.
+	fn receive_block(&mut self) -> io::Result<()> {
+		let block = match &self.result_receiver {
+			Some(receiver) => receiver.recv(),
+			None => return Err(io::Error::other("compression worker threads are not running")),
+		};
+		let block = match block {
+			Ok(x) => x,
+			Err(_) => return Err(io::Error::other("compression worker threads stopped unexpectedly")),
+		};
+		self.finished_blocks.insert(block.sequence_number, block);
+		while let Some(block) = self.finished_blocks.remove(&self.next_to_write) {
+			self.write_block(block)?;
+			self.next_to_write += 1;
+		}
+		Ok(())
+	}
+
+	fn write_block(&mut self, block: CompressedBlock) -> io::Result<()> {
+		let data = block.data?;
+		if !self.header_written {
+			self.write_header()?;
+		}
+		#[cfg(feature = "gzip")]
+		self.crc.combine(&block.crc);
+		self.write_to_inner(&data)
+	}
+
+	fn write_header(&mut self) -> io::Result<()> {
+		self.header_written = true;
+		match self.format {
+			#[cfg(feature = "gzip")]
+			CompressionFormat::Gzip => self.write_to_inner(&GZIP_HEADER),
+			#[cfg(feature = "zstd")]
+			CompressionFormat::Zstd => Ok(()),
+		}
+	}
+
+	fn write_trailer(&mut self) -> io::Result<()> {
+		match self.format {
+			#[cfg(feature = "gzip")]
+			CompressionFormat::Gzip => {
+				let crc = std::mem::replace(&mut self.crc, crc32fast::Hasher::new());
+				let mut trailer = DEFLATE_FINAL_BLOCK.to_vec();
+				// the amount of uncompressed bytes modulo 2^32.
+				let size = (self.total_in & 0xffff_ffff) as u32;
+				trailer.extend_from_slice(&crc.finalize().to_le_bytes());
+				trailer.extend_from_slice(&size.to_le_bytes());
+				self.write_to_inner(&trailer)
+			},
+			#[cfg(feature = "zstd")]
+			CompressionFormat::Zstd => Ok(()),
+		}
+	}
+
+	fn write_to_inner(&mut self, data: &[u8]) -> io::Result<()> {
+		match &mut self.obj {
+			Some(x) => x.write_all(data),
+			None => Err(io::Error::other("compressor is already finished")),
+		}
+	}
+}
+
+impl<W: io::Write> io::Write for ParallelCompressor<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if self.finished || self.obj.is_none() {
+			return Err(io::Error::other("compressor is already finished"));
+		}
+		let count = std::cmp::min(buf.len(), self.block_size - self.buffer.len());
+		self.buffer.extend_from_slice(&buf[..count]);
+		self.total_in += count as u64;
+		if self.buffer.len() == self.block_size {
+			let block = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.block_size));
+			self.submit(block)?;
+		}
+		Ok(count)
+	}
+
+	/// writes all blocks which are already compressed to the underlying writer (the current, incomplete block will not
+	/// be compressed, as this would decrease the compression ratio).
+	fn flush(&mut self) -> io::Result<()> {
+		while self.next_to_write < self.next_sequence_number {
+			self.receive_block()?;
+		}
+		match &mut self.obj {
+			Some(x) => x.flush(),
+			None => Ok(()),
+		}
+	}
+}
+
+impl<W: io::Write> Drop for ParallelCompressor<W> {
+	fn drop(&mut self) {
+		let _ = self.try_finish();
+	}
+}
+
+fn compress_block(format: CompressionFormat, level: i32, sequence_number: u64, data: &[u8]) -> CompressedBlock {
+	match format {
+		#[cfg(feature = "gzip")]
+		CompressionFormat::Gzip => {
+			let mut crc = crc32fast::Hasher::new();
+			crc.update(data);
+			CompressedBlock {
+				sequence_number,
+				data: deflate_block(level, data),
+				crc,
+			}
+		},
+		#[cfg(feature = "zstd")]
+		CompressionFormat::Zstd => CompressedBlock {
+			sequence_number,
+			data: zstd::bulk::compress(data, level),
+			#[cfg(feature = "gzip")]
+			crc: crc32fast::Hasher::new(),
+		},
+	}
+}
+
+// compresses the data to raw deflate blocks, which end on a byte boundary (sync flush), so the blocks of all workers
+// can be concatenated.
+#[cfg(feature = "gzip")]
+fn deflate_block(level: i32, data: &[u8]) -> io::Result<Vec<u8>> {
+	let mut compress = Compress::new(Compression::new(level.clamp(0, 9) as u32), false);
+	let mut output = Vec::with_capacity(data.len() / 2 + 64);
+	loop {
+		if output.capacity() - output.len() < 64 {
+			output.reserve(std::cmp::max(output.capacity(), 4096));
+		}
+		let consumed = compress.total_in() as usize;
+		if let Err(e) = compress.compress_vec(&data[consumed..], &mut output, FlushCompress::Sync) {
+			return Err(io::Error::other(e));
+		}
+		if compress.total_in() as usize == data.len() && output.len() < output.capacity() {
+			return Ok(output);
+		}
+	}
+}
\ No newline at end of file
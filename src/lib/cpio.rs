@@ -0,0 +1,473 @@
+/*************************************************************************
+* ph0llux:f3a1c09b27d4e8a65b0c9d1e7f26a4b8c3d05e91a7b62f48d0c1e3a5b7f92d64
+*************************************************************************/
+//!cpio Module (the "newc" format, e.g. used for initramfs images).
+
+// - STD
+use std::convert::TryFrom;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::time::SystemTime;
+
+// - internal
+use super::{PhollaitsError, PhollaitsErrorKind, Result};
+
+const CPIO_NEWC_MAGIC: &str = "070701";
+const CPIO_NEWC_CRC_MAGIC: &str = "070702";
+const CPIO_TRAILER: &str = "TRAILER!!!";
+const CPIO_HEADER_SIZE: usize = 110;
+// the maximum size of an entry name (including the trailing NUL), like PATH_MAX.
+const CPIO_MAX_NAME_SIZE: usize = 4096;
+const CPIO_BLOCK_SIZE: u64 = 512;
+
+const S_IFMT: u32 = 0o170000;
+const S_IFREG: u32 = 0o100000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFCHR: u32 = 0o020000;
+const S_IFBLK: u32 = 0o060000;
+
+/// The type of a device node in a cpio archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+	Character,
+	Block,
+}
+
+/// The header of an entry in a cpio ("newc") archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpioHeader {
+	pub ino: u32,
+	/// the file type and the "unix-like" file permissions (e.g. 0o100644 for a regular file).
+	pub mode: u32,
+	pub uid: u32,
+	pub gid: u32,
+	pub nlink: u32,
+	pub mtime: u32,
+	pub file_size: u32,
+	pub dev_major: u32,
+	pub dev_minor: u32,
+	pub rdev_major: u32,
+	pub rdev_minor: u32,
+	pub check: u32,
+	pub name: String,
+}
+
+impl CpioHeader {
+	// a leading "/" will be removed from the name.
+	fn new<N: Into<String>>(name: N, mode: u32) -> CpioHeader {
+		let name = name.into();
+		CpioHeader {
+			ino: 0,
+			mode,
+			uid: 0,
+			gid: 0,
+			nlink: 1,
+			mtime: current_mtime(),
+			file_size: 0,
+			dev_major: 0,
+			dev_minor: 0,
+			rdev_major: 0,
+			rdev_minor: 0,
+			check: 0,
+			name: name.trim_start_matches('/').to_string(),
+		}
+	}
+
+	/// returns true, if the entry is a regular file.
+	pub fn is_file(&self) -> bool {
+		self.mode & S_IFMT == S_IFREG
+	}
+
+	/// returns true, if the entry is a directory.
+	pub fn is_dir(&self) -> bool {
+		self.mode & S_IFMT == S_IFDIR
+	}
+
+	/// returns true, if the entry is a symbolic link (the target is stored as data of the entry).
+	pub fn is_symlink(&self) -> bool {
+		self.mode & S_IFMT == S_IFLNK
+	}
+
+	/// returns the type of the device node, if the entry is a device node.
+	pub fn device_type(&self) -> Option<DeviceType> {
+		match self.mode & S_IFMT {
+			S_IFCHR => Some(DeviceType::Character),
+			S_IFBLK => Some(DeviceType::Block),
+			_ => None,
+		}
+	}
+
+	fn to_bytes(&self) -> Vec<u8> {
+		let fields = [
+			self.ino, self.mode, self.uid, self.gid, self.nlink, self.mtime, self.file_size, self.dev_major,
+			self.dev_minor, self.rdev_major, self.rdev_minor, self.name.len() as u32 + 1, self.check,
+		];
+		let mut header = String::from(CPIO_NEWC_MAGIC);
+		for field in fields.iter() {
+			header.push_str(&format!("{:08x}", field));
+		}
+		let mut bytes = header.into_bytes();
+		bytes.extend_from_slice(self.name.as_bytes());
+		bytes.push(0);
+		bytes.resize(bytes.len() + padding(bytes.len() as u64), 0);
+		bytes
+	}
+}
+
+/// A builder for cpio archives in the "newc" format. The API is similar to [TarBuilderExt](crate::TarBuilderExt). A
+/// leading "/" will be removed from all names in the archive. The format limits the size of an entry to 4 GiB - 1.
+/// # Example
+/// ```
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+///		let file = fs::File::create("/tmp/initramfs.cpio").unwrap();
+/// 	let mut b = CpioBuilder::new(file);
+/// 	b.append_directory("dev", 493)?;
+/// 	b.append_device_node("dev/console", DeviceType::Character, 5, 1, 384)?;
+/// 	b.append_file_directly("assets/example.jpg")?;
+/// 	b.append_text("init", "#!/bin/sh\nexec /bin/sh\n")?;
+/// 	b.close_archive()
+/// }
+/// ```
+pub struct CpioBuilder<W: io::Write> {
+	obj: W,
+	next_ino: u32,
+	written: u64,
+}
+
+impl<W: io::Write> CpioBuilder<W> {
+	/// creates a new cpio archive builder, which writes the archive to the given writer.
+	pub fn new(obj: W) -> CpioBuilder<W> {
+		CpioBuilder {
+			obj,
+			next_ino: 1,
+			written: 0,
+		}
+	}
+
+	/// appends a file (or a directory entry) to an archive. A leading "/" will be removed from the name in the archive.
+	pub fn append_file_directly<P: Into<String>>(&mut self, path: P) -> Result<()> {
+		let path = path.into();
+		let name = path.clone();
+		let metadata = match fs::metadata(&path) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (metadata); {}", e)))
+		};
+		let permissions = file_permissions(&metadata);
+		if metadata.is_dir() {
+			return self.append_directory(name, permissions);
+		}
+		let mut header = CpioHeader::new(name, S_IFREG | permissions);
+		if let Some(n) = metadata.modified().ok().and_then(|x| x.duration_since(SystemTime::UNIX_EPOCH).ok()) {
+			header.mtime = n.as_secs() as u32;
+		};
+		header.file_size = file_size(metadata.len())?;
+		let file = match fs::File::open(&path) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append file directly (open); {}", e)))
+		};
+		self.append(header, file)
+	}
+
+	/// appends a text (string) to an archive. The text will be written as a textfile, with the "unix-like" file
+	/// permissions 644.
+	pub fn append_text<F: Into<String>, T: Into<String>>(&mut self, filename: F, text: T) -> Result<()> {
+		let text = text.into();
+		let mut header = CpioHeader::new(filename, S_IFREG | 420);
+		header.file_size = file_size(text.len() as u64)?;
+		self.append(header, text.as_bytes())
+	}
+
+	/// appends a directory with the given permissions (e.g. 493 -> 755) to an archive.
+	pub fn append_directory<N: Into<String>>(&mut self, name: N, mode: u32) -> Result<()> {
+		let mut header = CpioHeader::new(name, S_IFDIR | (mode & 0o7777));
+		header.nlink = 2;
+		self.append(header, io::empty())
+	}
+
+	/// appends a symbolic link to an archive.
+	pub fn append_symlink<N: Into<String>, T: Into<String>>(&mut self, name: N, target: T) -> Result<()> {
+		let target = target.into();
+		let mut header = CpioHeader::new(name, S_IFLNK | 511);
+		header.file_size = file_size(target.len() as u64)?;
+		self.append(header, target.as_bytes())
+	}
+
+	/// appends a character or block device node with the given major/minor number and permissions to an archive.
+	pub fn append_device_node<N: Into<String>>(
+		&mut self,
+		name: N,
+		device_type: DeviceType,
+		major: u32,
+		minor: u32,
+		mode: u32) -> Result<()> {
+		let file_type = match device_type {
+			DeviceType::Character => S_IFCHR,
+			DeviceType::Block => S_IFBLK,
+		};
+		let mut header = CpioHeader::new(name, file_type | (mode & 0o7777));
+		header.rdev_major = major;
+		header.rdev_minor = minor;
+		self.append(header, io::empty())
+	}
+
+	/// appends an entry with the given header. The data will be read until the file_size of the header is reached.
+	pub fn append<R: io::Read>(&mut self, mut header: CpioHeader, data: R) -> Result<()> {
+		header.ino = self.next_ino;
+		self.next_ino += 1;
+		let header_bytes = header.to_bytes();
+		self.write_all(&header_bytes)?;
+		let mut data = data.take(header.file_size as u64);
+		let copied = match io::copy(&mut data, &mut self.obj) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append data; {}", e)))
+		};
+		if copied != header.file_size as u64 {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to append data; expected {} bytes, got {}", header.file_size, copied)))
+		}
+		self.written += copied;
+		self.write_all(&vec![0; padding(copied)])
+	}
+
+	/// writes the "TRAILER!!!" record, pads the archive to a multiple of 512 bytes and flushes the underlying writer.
+	pub fn close_archive(mut self) -> Result<()> {
+		let mut trailer = CpioHeader::new(CPIO_TRAILER, 0);
+		trailer.mtime = 0;
+		trailer.nlink = 1;
+		self.write_all(&trailer.to_bytes())?;
+		let remainder = self.written % CPIO_BLOCK_SIZE;
+		if remainder != 0 {
+			self.write_all(&vec![0; (CPIO_BLOCK_SIZE - remainder) as usize])?;
+		}
+		match self.obj.flush() {
+			Ok(x) => Ok(x),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to close archive; {}", e)))
+		}
+	}
+
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		match self.obj.write_all(bytes) {
+			Ok(_) => {
+				self.written += bytes.len() as u64;
+				Ok(())
+			},
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to write archive; {}", e)))
+		}
+	}
+}
+
+/// A reader for cpio archives in the "newc" format.
+/// # Example
+/// ```
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use std::io::Read;
+///
+/// fn main() -> Result<()> {
+/// 	let mut archive = Vec::new();
+/// 	let mut b = CpioBuilder::new(&mut archive);
+/// 	b.append_text("/hello.txt", "Hello World!")?;
+/// 	b.close_archive()?;
+///
+/// 	let mut reader = CpioArchive::new(&archive[..]);
+/// 	while let Some(mut entry) = reader.next_entry()? {
+/// 		assert_eq!(entry.header().name, "hello.txt");
+/// 		assert_eq!(entry.read(&mut [0u8; 0]).unwrap(), 0);
+/// 		assert_eq!(entry.sha256sum()?, "7f83b1657ff1fc53b92dc18148a1d65dfc2d4b1fa3d677284addd200126d9069");
+/// 	}
+///
+/// 	// the name size of a header is limited.
+/// 	for name_size in &["00000000", "FFFFFFFF"] {
+/// 		let header = format!("070701{}{}00000000", "0".repeat(88), name_size);
+/// 		assert!(CpioArchive::new(header.as_bytes()).next_entry().is_err());
+/// 	}
+/// 	Ok(())
+/// }
+/// ```
+pub struct CpioArchive<R: io::Read> {
+	obj: R,
+	remaining: u64,
+	padding: u64,
+	finished: bool,
+}
+
+impl<R: io::Read> CpioArchive<R> {
+	/// creates a new cpio archive reader.
+	pub fn new(obj: R) -> CpioArchive<R> {
+		CpioArchive {
+			obj,
+			remaining: 0,
+			padding: 0,
+			finished: false,
+		}
+	}
+
+	/// returns the next entry of the archive or None, if the "TRAILER!!!" record was reached. Unread data of the
+	/// previous entry will be skipped.
+	pub fn next_entry(&mut self) -> Result<Option<CpioEntry<'_, R>>> {
+		if self.finished {
+			return Ok(None);
+		}
+		let skip = self.remaining + self.padding;
+		self.skip(skip)?;
+		let mut header_bytes = [0; CPIO_HEADER_SIZE];
+		self.read_exact(&mut header_bytes)?;
+		let magic = String::from_utf8_lossy(&header_bytes[..6]);
+		if magic != CPIO_NEWC_MAGIC && magic != CPIO_NEWC_CRC_MAGIC {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to read archive; invalid magic {}", magic)))
+		}
+		let mut fields = [0u32; 13];
+		for (i, field) in fields.iter_mut().enumerate() {
+			let hex = String::from_utf8_lossy(&header_bytes[6 + i * 8..14 + i * 8]);
+			*field = match u32::from_str_radix(&hex, 16) {
+				Ok(x) => x,
+				Err(e) => return Err(PhollaitsError::new(
+					PhollaitsErrorKind::ArchiveError,
+					format!("Error while trying to read archive; invalid header field {}: {}", hex, e)))
+			};
+		}
+		let name_size = fields[11] as usize;
+		if name_size == 0 || name_size > CPIO_MAX_NAME_SIZE {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to read archive; invalid name size {}", name_size)))
+		}
+		let mut name = vec![0; name_size];
+		self.read_exact(&mut name)?;
+		self.skip(padding((CPIO_HEADER_SIZE + name_size) as u64) as u64)?;
+		if name.last() == Some(&0) {
+			name.pop();
+		}
+		let header = CpioHeader {
+			ino: fields[0],
+			mode: fields[1],
+			uid: fields[2],
+			gid: fields[3],
+			nlink: fields[4],
+			mtime: fields[5],
+			file_size: fields[6],
+			dev_major: fields[7],
+			dev_minor: fields[8],
+			rdev_major: fields[9],
+			rdev_minor: fields[10],
+			check: fields[12],
+			name: String::from_utf8_lossy(&name).to_string(),
+		};
+		if header.name == CPIO_TRAILER {
+			self.finished = true;
+			return Ok(None);
+		}
+		self.remaining = header.file_size as u64;
+		self.padding = padding(header.file_size as u64) as u64;
+		Ok(Some(CpioEntry { header, archive: self }))
+	}
+
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		match self.obj.read_exact(buffer) {
+			Ok(x) => Ok(x),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to read archive; {}", e)))
+		}
+	}
+
+	fn skip(&mut self, count: u64) -> Result<()> {
+		match io::copy(&mut (&mut self.obj).take(count), &mut io::sink()) {
+			Ok(x) if x == count => Ok(()),
+			Ok(_) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				"Error while trying to read archive; unexpected end of archive")),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to read archive; {}", e)))
+		}
+	}
+}
+
+/// An entry of a [CpioArchive]. The data of the entry can be read by the [std::io::Read] implementation.
+pub struct CpioEntry<'a, R: io::Read> {
+	header: CpioHeader,
+	archive: &'a mut CpioArchive<R>,
+}
+
+impl<R: io::Read> CpioEntry<'_, R> {
+	/// returns the header of the entry.
+	pub fn header(&self) -> &CpioHeader {
+		&self.header
+	}
+}
+
+impl<R: io::Read> io::Read for CpioEntry<'_, R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if self.archive.remaining == 0 || buf.is_empty() {
+			return Ok(0);
+		}
+		let max = std::cmp::min(buf.len() as u64, self.archive.remaining) as usize;
+		let count = self.archive.obj.read(&mut buf[..max])?;
+		if count == 0 {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of archive"));
+		}
+		self.archive.remaining -= count as u64;
+		Ok(count)
+	}
+}
+
+// returns the length as file_size of a header, if it fits into the 32 bit field.
+fn file_size(len: u64) -> Result<u32> {
+	match u32::try_from(len) {
+		Ok(x) => Ok(x),
+		Err(_) => Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to append data; {} bytes exceed the maximum entry size of the cpio format", len)))
+	}
+}
+
+// returns the number of bytes needed to align the given length to 4 bytes.
+fn padding(len: u64) -> usize {
+	((4 - len % 4) % 4) as usize
+}
+
+fn current_mtime() -> u32 {
+	match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+		Ok(n) => n.as_secs() as u32,
+		Err(_) => 0
+	}
+}
+
+#[cfg(unix)]
+pub(crate) fn file_permissions(metadata: &fs::Metadata) -> u32 {
+	use std::os::unix::fs::PermissionsExt;
+	metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+pub(crate) fn file_permissions(metadata: &fs::Metadata) -> u32 {
+	if metadata.is_dir() {
+		493
+	} else if metadata.permissions().readonly() {
+		292
+	} else {
+		420
+	}
+}
\ No newline at end of file
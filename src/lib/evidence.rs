@@ -0,0 +1,587 @@
+/*************************************************************************
+* ph0llux:8e0d5a7c41f93b26e1d7a04c95b38f62a1e07d4c9b5f3a28e6d1c0b74f9a3e52
+*************************************************************************/
+//!evidence Module (forensic evidence bundles on top of tar).
+//!
+//! An evidence bundle is a plain tar archive with the following entries:
+//! - `evidence/<path>`: the source files.
+//! - `digests.txt`: one line per source file (tab separated): `path size md5 sha1 sha256`.
+//! - `case.meta`: the case metadata (one `key=value` pair per line).
+//! - `custody/<sequence number>.log`: the chain-of-custody records (one record per entry, tab separated):
+//!   `timestamp examiner action details previous`. The last field contains the sha256-digest of the previous record, so
+//!   a modified or removed record can be detected.
+//!
+//! The "sealed" record contains the sha256-digests of `digests.txt` and `case.meta`, so the evidence files (via their
+//! digests) and the case metadata are bound into the chain-of-custody as well. The chain only links every record to its
+//! predecessor: records, which were appended after sealing (e.g. by [log_bundle_access]), can be removed from the end
+//! of the log without being detected. Keep the digest of the last record (see [CustodyRecord::digest]) outside of the
+//! bundle, if this matters.
+
+// - STD
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::SystemTime;
+
+// - internal
+use super::{Digest, HashAlgorithm, HashDataExt, HashExt, HashingReader, PhollaitsError, PhollaitsErrorKind, Result, TarBuilderExt};
+
+// - external
+use tar::{Archive, Builder, Header};
+
+const EVIDENCE_DIRECTORY: &str = "evidence/";
+const CUSTODY_DIRECTORY: &str = "custody/";
+const CASE_METADATA_FILENAME: &str = "case.meta";
+const DIGESTS_FILENAME: &str = "digests.txt";
+const EVIDENCE_ALGORITHMS: [HashAlgorithm; 3] = [HashAlgorithm::Md5, HashAlgorithm::Sha1, HashAlgorithm::Sha256];
+
+/// The metadata of a case, which will be stored as `case.meta` in an [EvidenceBundle].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseMetadata {
+	pub case_id: String,
+	pub examiner: String,
+	/// the creation time of the bundle (seconds since UNIX_EPOCH).
+	pub created: u64,
+	/// the time the bundle was sealed (seconds since UNIX_EPOCH).
+	pub sealed: Option<u64>,
+	pub notes: Vec<String>,
+}
+
+impl CaseMetadata {
+	/// creates new case metadata with the current time as creation time.
+	pub fn new<C: Into<String>, E: Into<String>>(case_id: C, examiner: E) -> CaseMetadata {
+		CaseMetadata {
+			case_id: case_id.into(),
+			examiner: examiner.into(),
+			created: current_timestamp(),
+			sealed: None,
+			notes: Vec::new(),
+		}
+	}
+
+	fn to_text(&self) -> String {
+		let mut text = format!("case_id={}\nexaminer={}\ncreated={}\n", escape(&self.case_id), escape(&self.examiner), self.created);
+		if let Some(sealed) = self.sealed {
+			text.push_str(&format!("sealed={}\n", sealed));
+		}
+		for note in &self.notes {
+			text.push_str(&format!("note={}\n", escape(note)));
+		}
+		text
+	}
+
+	fn from_text(text: &str) -> Result<CaseMetadata> {
+		let mut metadata = CaseMetadata {
+			case_id: String::new(),
+			examiner: String::new(),
+			created: 0,
+			sealed: None,
+			notes: Vec::new(),
+		};
+		for line in text.lines().filter(|line| !line.is_empty()) {
+			let (key, value) = match line.find('=') {
+				Some(x) => (&line[..x], unescape(&line[x + 1..])),
+				None => return Err(invalid_bundle(format!("malformed line in {}: {}", CASE_METADATA_FILENAME, line))),
+			};
+			match key {
+				"case_id" => metadata.case_id = value,
+				"examiner" => metadata.examiner = value,
+				"created" => metadata.created = value.parse()?,
+				"sealed" => metadata.sealed = Some(value.parse()?),
+				"note" => metadata.notes.push(value),
+				_ => return Err(invalid_bundle(format!("unknown key in {}: {}", CASE_METADATA_FILENAME, key))),
+			}
+		}
+		Ok(metadata)
+	}
+}
+
+/// The digests of a source file in an [EvidenceBundle].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvidenceDigests {
+	pub size: u64,
+	pub md5: String,
+	pub sha1: String,
+	pub sha256: String,
+}
+
+/// A record of the chain-of-custody log of an [EvidenceBundle].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustodyRecord {
+	/// the time of the record (seconds since UNIX_EPOCH).
+	pub timestamp: u64,
+	pub examiner: String,
+	pub action: String,
+	pub details: String,
+	/// the sha256-digest of the previous record (empty for the first record).
+	pub previous: String,
+}
+
+impl CustodyRecord {
+	fn new<E: Into<String>, A: Into<String>, D: Into<String>>(examiner: E, action: A, details: D, previous: String) -> CustodyRecord {
+		CustodyRecord {
+			timestamp: current_timestamp(),
+			examiner: examiner.into(),
+			action: action.into(),
+			details: details.into(),
+			previous,
+		}
+	}
+
+	fn to_line(&self) -> String {
+		format!("{}\t{}\t{}\t{}\t{}\n",
+			self.timestamp, escape(&self.examiner), escape(&self.action), escape(&self.details), self.previous)
+	}
+
+	fn from_line(line: &str) -> Result<CustodyRecord> {
+		let fields: Vec<&str> = line.trim_end_matches('\n').split('\t').collect();
+		if fields.len() != 5 {
+			return Err(invalid_bundle(format!("malformed custody record: {}", line)));
+		}
+		Ok(CustodyRecord {
+			timestamp: fields[0].parse()?,
+			examiner: unescape(fields[1]),
+			action: unescape(fields[2]),
+			details: unescape(fields[3]),
+			previous: fields[4].to_string(),
+		})
+	}
+
+	/// returns the sha256-digest of this record, which has to be stored in the next record.
+	pub fn digest(&self) -> Result<String> {
+		self.to_line().sha256sum()
+	}
+}
+
+/// A builder for forensic evidence bundles (see the [module documentation](self) for the layout).
+/// # Example
+/// ```
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let mut metadata = CaseMetadata::new("2021-0042", "ph0llux");
+/// 	metadata.notes.push("seized at the suspect's desk".to_string());
+/// 	let mut bundle = EvidenceBundle::create("/tmp/evidence_bundle.tar", metadata)?;
+/// 	let digests = bundle.add_evidence("assets/example.jpg")?;
+/// 	assert_eq!(digests.md5, "a4494bd1b83303bc0872a996e6c8a8bf");
+/// 	bundle.close_archive()?;
+///
+/// 	let verification = verify_evidence_bundle("/tmp/evidence_bundle.tar", "reviewer")?;
+/// 	assert!(verification.is_valid());
+/// 	assert_eq!(verification.metadata.case_id, "2021-0042");
+/// 	// created, acquired, sealed and the access by the verifier itself.
+/// 	assert_eq!(read_custody_log("/tmp/evidence_bundle.tar", "reviewer")?.len(), 4);
+/// 	Ok(())
+/// }
+/// ```
+pub struct EvidenceBundle<W: io::Write> {
+	builder: Builder<W>,
+	metadata: CaseMetadata,
+	digests: BTreeMap<String, EvidenceDigests>,
+	custody_log: Vec<CustodyRecord>,
+}
+
+impl EvidenceBundle<File> {
+	/// creates a new evidence bundle file at the given path.
+	pub fn create<P: AsRef<Path>>(path: P, metadata: CaseMetadata) -> Result<EvidenceBundle<File>> {
+		match File::create(path) {
+			Ok(file) => EvidenceBundle::new(file, metadata),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to create evidence bundle; {}", e)))
+		}
+	}
+}
+
+impl<W: io::Write> EvidenceBundle<W> {
+	/// creates a new evidence bundle, which will be written to the given writer.
+	pub fn new(obj: W, metadata: CaseMetadata) -> Result<EvidenceBundle<W>> {
+		let mut bundle = EvidenceBundle {
+			builder: Builder::new(obj),
+			metadata,
+			digests: BTreeMap::new(),
+			custody_log: Vec::new(),
+		};
+		let details = format!("case {}", bundle.metadata.case_id);
+		bundle.log("created", details)?;
+		Ok(bundle)
+	}
+
+	/// returns the case metadata of this bundle.
+	pub fn metadata(&self) -> &CaseMetadata {
+		&self.metadata
+	}
+
+	/// adds a note to the case metadata.
+	pub fn add_note<N: Into<String>>(&mut self, note: N) {
+		self.metadata.notes.push(note.into())
+	}
+
+	/// appends a source file as `evidence/<path>` and records its md5, sha1 and sha256 digests. The digests are
+	/// calculated in the same pass, which writes the data to the archive, so they always describe the archived bytes.
+	pub fn add_evidence<P: Into<String>>(&mut self, path: P) -> Result<EvidenceDigests> {
+		let path = path.into();
+		let name = format!("{}{}", EVIDENCE_DIRECTORY, path.trim_start_matches('/'));
+		let file = match File::open(&path) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to add evidence (open); {}", e)))
+		};
+		let metadata = match file.metadata() {
+			Ok(x) => x,
+			Err(e) => return Err(archive_error(e)),
+		};
+		let mut header = Header::new_gnu();
+		header.set_metadata(&metadata);
+		// the size in the header is fixed, so a growing file is cut off (and a shrinking file is detected below).
+		let mut reader = HashingReader::new(file.take(metadata.len()), &EVIDENCE_ALGORITHMS);
+		if let Err(e) = self.builder.append_data(&mut header, &name, &mut reader) {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to add evidence (append_data); {}", e)))
+		};
+		let size = reader.byte_count();
+		if size != metadata.len() {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to add evidence; {} was truncated while reading", path)))
+		}
+		let digests = to_evidence_digests(size, &reader.finalize());
+		self.log("acquired", format!("{} (sha256 {})", path, digests.sha256))?;
+		self.digests.insert(name, digests.clone());
+		Ok(digests)
+	}
+
+	/// appends a record to the chain-of-custody log.
+	pub fn log<A: Into<String>, D: Into<String>>(&mut self, action: A, details: D) -> Result<()> {
+		let previous = match self.custody_log.last() {
+			Some(x) => x.digest()?,
+			None => String::new(),
+		};
+		let record = CustodyRecord::new(self.metadata.examiner.clone(), action, details, previous);
+		let filename = custody_filename(self.custody_log.len() + 1);
+		self.builder.append_text(filename, record.to_line())?;
+		self.custody_log.push(record);
+		Ok(())
+	}
+
+	/// seals the bundle: writes the digests and the case metadata, binds both into the chain-of-custody (by the "sealed"
+	/// record) and finishes the archive.
+	pub fn close_archive(mut self) -> Result<()> {
+		let mut digests = String::new();
+		for (name, digest) in &self.digests {
+			digests.push_str(&format!("{}\t{}\t{}\t{}\t{}\n", escape(name), digest.size, digest.md5, digest.sha1, digest.sha256));
+		}
+		self.metadata.sealed = Some(current_timestamp());
+		let metadata = self.metadata.to_text();
+		let details = format!("{} evidence file(s); {}; {}",
+			self.digests.len(), seal_digest(DIGESTS_FILENAME, &digests)?, seal_digest(CASE_METADATA_FILENAME, &metadata)?);
+		self.builder.append_text(DIGESTS_FILENAME, digests)?;
+		self.log("sealed", details)?;
+		self.builder.append_text(CASE_METADATA_FILENAME, metadata)?;
+		self.builder.close_archive()
+	}
+}
+
+/// The result of the verification of a single source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvidenceCheck {
+	/// the path inside of the bundle.
+	pub path: String,
+	/// the digests recorded in `digests.txt` (None, if the file is not listed).
+	pub expected: Option<EvidenceDigests>,
+	/// the digests of the stored data (None, if the file is missing).
+	pub actual: Option<EvidenceDigests>,
+}
+
+impl EvidenceCheck {
+	/// returns true, if the file is listed, present and all digests match.
+	pub fn is_valid(&self) -> bool {
+		self.expected.is_some() && self.expected == self.actual
+	}
+}
+
+/// The result of [verify_evidence_bundle].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleVerification {
+	pub metadata: CaseMetadata,
+	pub evidence: Vec<EvidenceCheck>,
+	/// the chain-of-custody log, as it was found before the verification was recorded.
+	pub custody_log: Vec<CustodyRecord>,
+	/// all problems found (e.g. a broken chain-of-custody).
+	pub errors: Vec<String>,
+}
+
+impl BundleVerification {
+	/// returns true, if no problems were found.
+	pub fn is_valid(&self) -> bool {
+		self.errors.is_empty() && self.evidence.iter().all(|check| check.is_valid())
+	}
+}
+
+/// verifies the whole evidence bundle (case metadata, all evidence digests and the chain-of-custody, including the
+/// digests of `digests.txt` and `case.meta` in the "sealed" record) and records the access (including the result) in
+/// the chain-of-custody log.
+pub fn verify_evidence_bundle<P: AsRef<Path>, E: Into<String>>(path: P, examiner: E) -> Result<BundleVerification> {
+	let path = path.as_ref();
+	let contents = read_bundle(path, true)?;
+	let mut errors = Vec::new();
+	let metadata = match &contents.metadata {
+		Some(x) => CaseMetadata::from_text(x)?,
+		None => {
+			errors.push(format!("{} is missing", CASE_METADATA_FILENAME));
+			CaseMetadata::new("", "")
+		}
+	};
+	if metadata.sealed.is_none() {
+		errors.push("bundle is not sealed".to_string());
+	}
+	let mut expected = BTreeMap::new();
+	match &contents.digests {
+		Some(x) => for line in x.lines() {
+			let fields: Vec<&str> = line.split('\t').collect();
+			if fields.len() != 5 {
+				errors.push(format!("malformed line in {}: {}", DIGESTS_FILENAME, line));
+				continue;
+			}
+			expected.insert(unescape(fields[0]), EvidenceDigests {
+				size: fields[1].parse()?,
+				md5: fields[2].to_string(),
+				sha1: fields[3].to_string(),
+				sha256: fields[4].to_string(),
+			});
+		},
+		None => errors.push(format!("{} is missing", DIGESTS_FILENAME)),
+	}
+	let mut evidence = Vec::new();
+	for (name, digests) in &expected {
+		evidence.push(EvidenceCheck {
+			path: name.clone(),
+			expected: Some(digests.clone()),
+			actual: contents.evidence.get(name).cloned(),
+		});
+	}
+	for (name, digests) in &contents.evidence {
+		if !expected.contains_key(name) {
+			evidence.push(EvidenceCheck { path: name.clone(), expected: None, actual: Some(digests.clone()) });
+		}
+	}
+	errors.extend(check_custody_chain(&contents.custody_log));
+	errors.extend(check_seal(&contents));
+	let verification = BundleVerification {
+		metadata,
+		evidence,
+		custody_log: contents.custody_log,
+		errors,
+	};
+	let result = if verification.is_valid() { "OK" } else { "FAILED" };
+	log_bundle_access(path, examiner, "verified", result)?;
+	Ok(verification)
+}
+
+/// appends a record to the chain-of-custody log of an existing evidence bundle and returns the new record.
+pub fn log_bundle_access<P: AsRef<Path>, E: Into<String>, A: Into<String>, D: Into<String>>(
+	path: P,
+	examiner: E,
+	action: A,
+	details: D) -> Result<CustodyRecord> {
+	let path = path.as_ref();
+	let contents = read_bundle(path, false)?;
+	let previous = match contents.custody_log.last() {
+		Some(x) => x.digest()?,
+		None => String::new(),
+	};
+	let record = CustodyRecord::new(examiner, action, details, previous);
+	let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+		Ok(x) => x,
+		Err(e) => return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to open evidence bundle; {}", e)))
+	};
+	// overwrites the end-of-archive marker with the new record.
+	if let Err(e) = file.set_len(contents.end_of_entries).and_then(|_| file.seek(SeekFrom::Start(contents.end_of_entries))) {
+		return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to append to evidence bundle; {}", e)))
+	};
+	let mut builder = Builder::new(file);
+	builder.append_text(custody_filename(contents.custody_log.len() + 1), record.to_line())?;
+	builder.close_archive()?;
+	Ok(record)
+}
+
+/// returns the chain-of-custody log of an evidence bundle, as it was found before the access was recorded in the log.
+pub fn read_custody_log<P: AsRef<Path>, E: Into<String>>(path: P, examiner: E) -> Result<Vec<CustodyRecord>> {
+	let path = path.as_ref();
+	let custody_log = read_bundle(path, false)?.custody_log;
+	log_bundle_access(path, examiner, "read custody log", format!("{} record(s)", custody_log.len()))?;
+	Ok(custody_log)
+}
+
+struct BundleContents {
+	metadata: Option<String>,
+	digests: Option<String>,
+	evidence: BTreeMap<String, EvidenceDigests>,
+	custody_log: Vec<CustodyRecord>,
+	end_of_entries: u64,
+}
+
+// reads the bundle; the evidence files are only hashed, if hash_evidence is set.
+fn read_bundle(path: &Path, hash_evidence: bool) -> Result<BundleContents> {
+	let file = match File::open(path) {
+		Ok(x) => x,
+		Err(e) => return Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to open evidence bundle; {}", e)))
+	};
+	let mut contents = BundleContents {
+		metadata: None,
+		digests: None,
+		evidence: BTreeMap::new(),
+		custody_log: Vec::new(),
+		end_of_entries: 0,
+	};
+	let mut custody = BTreeMap::new();
+	let mut archive = Archive::new(file);
+	for entry in archive.entries().map_err(archive_error)? {
+		let mut entry = entry.map_err(archive_error)?;
+		let name = entry.path().map_err(archive_error)?.to_string_lossy().to_string();
+		let size = entry.header().entry_size().map_err(archive_error)?;
+		contents.end_of_entries = entry.raw_file_position() + size.div_ceil(512) * 512;
+		if name.starts_with(EVIDENCE_DIRECTORY) {
+			if !hash_evidence {
+				continue;
+			}
+			contents.evidence.insert(name, evidence_digests(&mut entry)?);
+		} else if name.starts_with(CUSTODY_DIRECTORY) {
+			custody.insert(name, CustodyRecord::from_line(&read_text(&mut entry)?)?);
+		} else if name == DIGESTS_FILENAME {
+			contents.digests = Some(read_text(&mut entry)?);
+		} else if name == CASE_METADATA_FILENAME {
+			contents.metadata = Some(read_text(&mut entry)?);
+		}
+	}
+	contents.custody_log = custody.into_values().collect();
+	Ok(contents)
+}
+
+fn check_custody_chain(custody_log: &[CustodyRecord]) -> Vec<String> {
+	let mut errors = Vec::new();
+	let mut previous = String::new();
+	for (number, record) in custody_log.iter().enumerate() {
+		if record.previous != previous {
+			errors.push(format!("chain-of-custody is broken at record {}", number + 1));
+		}
+		previous = match record.digest() {
+			Ok(x) => x,
+			Err(e) => {
+				errors.push(e.to_string());
+				String::new()
+			}
+		};
+	}
+	if custody_log.is_empty() {
+		errors.push("chain-of-custody log is missing".to_string());
+	}
+	errors
+}
+
+// checks, that the "sealed" record contains the digests of the stored digests.txt and case.meta.
+fn check_seal(contents: &BundleContents) -> Vec<String> {
+	let sealed: Vec<&CustodyRecord> = contents.custody_log.iter().filter(|record| record.action == "sealed").collect();
+	let record = match sealed[..] {
+		[record] => record,
+		[] => return vec!["chain-of-custody contains no sealed record".to_string()],
+		_ => return vec!["chain-of-custody contains more than one sealed record".to_string()],
+	};
+	let mut errors = Vec::new();
+	for (filename, text) in &[(DIGESTS_FILENAME, &contents.digests), (CASE_METADATA_FILENAME, &contents.metadata)] {
+		let text = match text {
+			Some(x) => x,
+			None => continue,
+		};
+		match seal_digest(filename, text) {
+			Ok(x) if record.details.split("; ").any(|part| part == x) => (),
+			Ok(_) => errors.push(format!("{} does not match the sealed digest", filename)),
+			Err(e) => errors.push(e.to_string()),
+		}
+	}
+	errors
+}
+
+// returns the part of the details of the "sealed" record, which binds the file into the chain-of-custody.
+fn seal_digest(filename: &str, text: &str) -> Result<String> {
+	Ok(format!("{} sha256 {}", filename, text.sha256sum()?))
+}
+
+// calculates md5, sha1 and sha256 in one pass, as the data of an archive entry can only be read once.
+fn evidence_digests<R: io::Read>(reader: &mut R) -> Result<EvidenceDigests> {
+	// the reader only counts the bytes, the digests are calculated by multi_digest.
+	let mut reader = HashingReader::new(reader, &[]);
+	let digests = reader.multi_digest(&EVIDENCE_ALGORITHMS)?;
+	Ok(to_evidence_digests(reader.byte_count(), &digests))
+}
+
+fn to_evidence_digests(size: u64, digests: &BTreeMap<HashAlgorithm, Digest>) -> EvidenceDigests {
+	let hex_digest = |algorithm| digests.get(&algorithm).map(|digest| digest.to_hex()).unwrap_or_default();
+	EvidenceDigests {
+		size,
+		md5: hex_digest(HashAlgorithm::Md5),
+		sha1: hex_digest(HashAlgorithm::Sha1),
+		sha256: hex_digest(HashAlgorithm::Sha256),
+	}
+}
+
+fn read_text<R: io::Read>(reader: &mut R) -> Result<String> {
+	let mut text = String::new();
+	match reader.read_to_string(&mut text) {
+		Ok(_) => Ok(text),
+		Err(e) => Err(archive_error(e))
+	}
+}
+
+fn custody_filename(sequence_number: usize) -> String {
+	format!("{}{:06}.log", CUSTODY_DIRECTORY, sequence_number)
+}
+
+fn current_timestamp() -> u64 {
+	match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+		Ok(n) => n.as_secs(),
+		Err(_) => 0
+	}
+}
+
+fn escape(value: &str) -> String {
+	value.replace('\\', "\\\\").replace('\n', "\\n").replace('\t', "\\t")
+}
+
+fn unescape(value: &str) -> String {
+	let mut unescaped = String::new();
+	let mut chars = value.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			unescaped.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('n') => unescaped.push('\n'),
+			Some('t') => unescaped.push('\t'),
+			Some(x) => unescaped.push(x),
+			None => unescaped.push('\\'),
+		}
+	}
+	unescaped
+}
+
+fn archive_error(e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(PhollaitsErrorKind::ArchiveError, format!("Error while trying to read evidence bundle; {}", e))
+}
+
+fn invalid_bundle<S: Into<String>>(details: S) -> PhollaitsError {
+	PhollaitsError::new(PhollaitsErrorKind::ArchiveError, format!("Invalid evidence bundle; {}", details.into()))
+}
\ No newline at end of file
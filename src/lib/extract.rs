@@ -0,0 +1,778 @@
+/*************************************************************************
+* ph0llux:8e2d4b7a1f05c39e6d8b2a0f4c7e13d95b6a08f2e1c4d7b39a05f6e2c8d1b4a7
+*************************************************************************/
+//!extract Module (extract archives with metadata restoration, overwrite policies and dry-runs).
+
+// - STD
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// - internal
+use super::{auto_decompress, HashExt, PhollaitsError, PhollaitsErrorKind, Result};
+
+// - external
+use filetime::FileTime;
+use tar::{Archive, Entry, EntryType};
+
+/// Decides what happens, if the destination of an entry already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverwritePolicy {
+	/// keeps the existing file and skips the entry.
+	#[default]
+	Never,
+	/// replaces the existing file, if the mtime of the entry is newer than the mtime of the existing file.
+	IfNewer,
+	/// always replaces the existing file.
+	Always,
+	/// keeps the existing file and extracts the entry to "<name>.<n>", using the first free number n.
+	Rename,
+}
+
+/// The reason why an entry was skipped by the [ArchiveExtractor].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+	/// the destination already exists (see [OverwritePolicy::Never]).
+	Exists,
+	/// the destination already exists and is not older than the entry (see [OverwritePolicy::IfNewer]).
+	NotNewer,
+	/// the path of the entry (or the target of a hardlink) would escape the destination directory.
+	UnsafePath,
+	/// the type of the entry (e.g. a device node or a fifo) is not supported.
+	UnsupportedType,
+}
+
+/// The filesystem operation for a single entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractOperation {
+	/// creates a new file, directory or link.
+	Create,
+	/// replaces an existing file (or updates the metadata of an existing directory).
+	Overwrite,
+	/// creates the entry under a new name, as the destination already exists (see [OverwritePolicy::Rename]).
+	Rename,
+	/// does not touch the filesystem.
+	Skip(SkipReason),
+}
+
+/// The report for a single entry of the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedEntry {
+	/// the path of the entry in the archive.
+	pub path: PathBuf,
+	/// the (planned) destination on the filesystem.
+	pub destination: PathBuf,
+	pub entry_type: EntryType,
+	pub operation: ExtractOperation,
+}
+
+/// The result of [ArchiveExtractor::extract].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractReport {
+	/// all entries, in the order of the archive.
+	pub entries: Vec<ExtractedEntry>,
+	/// true, if the report only contains the planned operations (see [ArchiveExtractor::dry_run]).
+	pub dry_run: bool,
+}
+
+impl ExtractReport {
+	/// returns the entries, which were (or would be) written to the filesystem.
+	pub fn written(&self) -> impl Iterator<Item = &ExtractedEntry> {
+		self.entries.iter().filter(|e| !matches!(e.operation, ExtractOperation::Skip(_)))
+	}
+
+	/// returns the skipped entries.
+	pub fn skipped(&self) -> impl Iterator<Item = &ExtractedEntry> {
+		self.entries.iter().filter(|e| matches!(e.operation, ExtractOperation::Skip(_)))
+	}
+}
+
+/// An extractor for (gzip or zstd compressed, see [auto_decompress]) tar archives, which restores the metadata of the
+/// entries: permissions, mtime and atime (if the archive contains GNU or PAX atimes), extended attributes and the
+/// ownership (only if the process is running as root).
+/// The leading "/" of absolute paths is stripped (like GNU tar does), so these entries are extracted below the
+/// destination; entries with ".." components are skipped. Extracting an entry below a symlink (or any other
+/// non-directory) fails, before anything is created. Regular files, directories, symlinks and hardlinks are supported,
+/// all other entry types are skipped. The content of a directory, which was renamed (see [OverwritePolicy::Rename]), will
+/// be extracted into the renamed directory.
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use tar::Builder;
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+/// 	let mut archive = Vec::new();
+/// 	let mut b = Builder::new(&mut archive);
+/// 	b.append_text("example01.txt", "first version")?;
+/// 	b.close_archive()?;
+///
+/// 	let destination = std::env::temp_dir().join("phollaits_extract_example");
+/// 	let _ = fs::remove_dir_all(&destination);
+/// 	let report = ArchiveExtractor::new(&destination).extract(&archive[..])?;
+/// 	assert_eq!(report.entries[0].operation, ExtractOperation::Create);
+///
+/// 	// a dry-run only returns the planned operations.
+/// 	let report = ArchiveExtractor::new(&destination)
+/// 		.overwrite(OverwritePolicy::Rename)
+/// 		.dry_run(true)
+/// 		.extract(&archive[..])?;
+/// 	assert_eq!(report.entries[0].operation, ExtractOperation::Rename);
+/// 	assert_eq!(report.entries[0].destination, destination.join("example01.txt.1"));
+/// 	assert!(!destination.join("example01.txt.1").exists());
+///
+/// 	let report = ArchiveExtractor::new(&destination).extract(&archive[..])?;
+/// 	assert_eq!(report.entries[0].operation, ExtractOperation::Skip(SkipReason::Exists));
+/// 	fs::remove_dir_all(&destination).unwrap();
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArchiveExtractor {
+	destination: PathBuf,
+	overwrite: OverwritePolicy,
+	preserve_permissions: bool,
+	preserve_times: bool,
+	restore_ownership: bool,
+	unpack_xattrs: bool,
+	dry_run: bool,
+}
+
+impl ArchiveExtractor {
+	/// returns a new extractor for the given destination directory (which will be created, if necessary).
+	pub fn new<P: Into<PathBuf>>(destination: P) -> ArchiveExtractor {
+		ArchiveExtractor {
+			destination: destination.into(),
+			overwrite: OverwritePolicy::default(),
+			preserve_permissions: true,
+			preserve_times: true,
+			restore_ownership: true,
+			unpack_xattrs: true,
+			dry_run: false,
+		}
+	}
+
+	/// sets the overwrite policy (default: [OverwritePolicy::Never]).
+	pub fn overwrite(mut self, policy: OverwritePolicy) -> ArchiveExtractor {
+		self.overwrite = policy;
+		self
+	}
+
+	/// restores the full permissions, including the setuid, setgid and sticky bits (default: true). Otherwise, only
+	/// the rwx bits will be restored.
+	pub fn preserve_permissions(mut self, preserve: bool) -> ArchiveExtractor {
+		self.preserve_permissions = preserve;
+		self
+	}
+
+	/// restores the mtime and atime of the entries (default: true).
+	pub fn preserve_times(mut self, preserve: bool) -> ArchiveExtractor {
+		self.preserve_times = preserve;
+		self
+	}
+
+	/// restores the uid and gid of the entries (default: true). Only takes effect, if the process is running as root.
+	pub fn restore_ownership(mut self, restore: bool) -> ArchiveExtractor {
+		self.restore_ownership = restore;
+		self
+	}
+
+	/// restores the extended attributes of the entries (default: true). Only takes effect on unix.
+	pub fn unpack_xattrs(mut self, unpack: bool) -> ArchiveExtractor {
+		self.unpack_xattrs = unpack;
+		self
+	}
+
+	/// if enabled, the filesystem will not be touched; [ArchiveExtractor::extract] only returns the planned
+	/// operations (default: false).
+	pub fn dry_run(mut self, dry_run: bool) -> ArchiveExtractor {
+		self.dry_run = dry_run;
+		self
+	}
+
+	/// extracts the archive and returns a report of the operations for each entry.
+	/// # Example
+	/// ```
+	/// extern crate tar;
+	/// extern crate phollaits;
+	///
+	/// use phollaits::*;
+	/// use tar::{Builder, EntryType, Header};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let outside = std::env::temp_dir().join("phollaits_extract_outside");
+	/// 	let _ = fs::remove_dir_all(&outside);
+	/// 	fs::create_dir_all(&outside).unwrap();
+	///
+	/// 	// a symlink pointing outside of the destination, followed by an entry below the symlink.
+	/// 	let mut archive = Vec::new();
+	/// 	let mut b = Builder::new(&mut archive);
+	/// 	let mut header = Header::new_gnu();
+	/// 	header.set_entry_type(EntryType::Symlink);
+	/// 	header.set_size(0);
+	/// 	header.set_mode(511);
+	/// 	b.append_link(&mut header, "link", &outside).unwrap();
+	/// 	b.append_text("link/a/b/file", "escaped")?;
+	/// 	b.close_archive()?;
+	///
+	/// 	let destination = std::env::temp_dir().join("phollaits_extract_symlink");
+	/// 	let _ = fs::remove_dir_all(&destination);
+	/// 	assert!(ArchiveExtractor::new(&destination).extract(&archive[..]).is_err());
+	/// 	assert!(fs::symlink_metadata(destination.join("link")).unwrap().file_type().is_symlink());
+	/// 	assert!(!outside.join("a").exists());
+	/// 	fs::remove_dir_all(&destination).unwrap();
+	/// 	fs::remove_dir_all(&outside).unwrap();
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn extract<R: io::Read>(&self, reader: R) -> Result<ExtractReport> {
+		let mut archive = Archive::new(auto_decompress(reader)?);
+		archive.set_preserve_permissions(self.preserve_permissions);
+		archive.set_preserve_mtime(self.preserve_times);
+		archive.set_unpack_xattrs(self.unpack_xattrs);
+		archive.set_overwrite(true);
+
+		if !self.dry_run {
+			fs::create_dir_all(&self.destination).map_err(|e| extract_error(&self.destination, e))?;
+		}
+		let mut report = ExtractReport {
+			entries: Vec::new(),
+			dry_run: self.dry_run,
+		};
+		// the destinations, which were already used by earlier entries (relevant for the rename policy).
+		let mut planned = HashSet::new();
+		// the times of directories have to be restored at the end, as extracting their content changes the mtime.
+		let mut directory_times = Vec::new();
+		// the directories, which were renamed (see [OverwritePolicy::Rename]); their content follows them.
+		let mut renamed_directories = Vec::new();
+		let entries = archive.entries().map_err(|e| extract_error(&self.destination, e))?;
+		for entry in entries {
+			let mut entry = entry.map_err(|e| extract_error(&self.destination, e))?;
+			let path = entry.path().map_err(|e| extract_error(&self.destination, e))?.into_owned();
+			let entry_type = entry.header().entry_type();
+			let mut extracted = ExtractedEntry {
+				destination: self.destination.join(&path),
+				path,
+				entry_type,
+				operation: ExtractOperation::Create,
+			};
+			if !is_supported(entry_type) {
+				extracted.operation = ExtractOperation::Skip(SkipReason::UnsupportedType);
+				report.entries.push(extracted);
+				continue;
+			}
+			let relative_path = match sanitize_path(&extracted.path) {
+				Some(x) => x,
+				None => {
+					extracted.operation = ExtractOperation::Skip(SkipReason::UnsafePath);
+					report.entries.push(extracted);
+					continue;
+				}
+			};
+			extracted.destination = self.destination_of(&relative_path, &renamed_directories);
+			self.plan(&entry, &mut extracted, &planned)?;
+			if let ExtractOperation::Skip(_) = extracted.operation {
+				report.entries.push(extracted);
+				continue;
+			}
+			let link_target = if entry_type.is_hard_link() {
+				let target = entry.link_name().map_err(|e| extract_error(&extracted.destination, e))?;
+				match target.as_ref().and_then(|target| sanitize_path(target)) {
+					Some(target) => Some(self.destination_of(&target, &renamed_directories)),
+					None => {
+						extracted.operation = ExtractOperation::Skip(SkipReason::UnsafePath);
+						report.entries.push(extracted);
+						continue;
+					}
+				}
+			} else {
+				None
+			};
+			planned.insert(extracted.destination.clone());
+			if entry_type.is_dir() && extracted.operation == ExtractOperation::Rename {
+				renamed_directories.push((relative_path, extracted.destination.clone()));
+			}
+			if !self.dry_run {
+				self.unpack(&mut entry, &extracted, link_target)?;
+				if self.preserve_times {
+					if let Some((atime, mtime)) = entry_times(&mut entry) {
+						if entry_type.is_dir() {
+							directory_times.push((extracted.destination.clone(), (atime, mtime)));
+						} else if atime != mtime {
+							// tar already sets the atime to the mtime of the entry.
+							set_times(&extracted.destination, entry_type, (atime, mtime))?;
+						}
+					}
+				}
+			}
+			report.entries.push(extracted);
+		}
+		for (destination, times) in directory_times.into_iter().rev() {
+			set_times(&destination, EntryType::Directory, times)?;
+		}
+		Ok(report)
+	}
+
+	// returns the destination of the relative path, inside of the innermost renamed parent directory (if any).
+	fn destination_of(&self, relative_path: &Path, renamed_directories: &[(PathBuf, PathBuf)]) -> PathBuf {
+		renamed_directories.iter()
+			.filter_map(|(directory, destination)| Some((directory, destination.join(relative_path.strip_prefix(directory).ok()?))))
+			.max_by_key(|(directory, _)| directory.components().count())
+			.map(|(_, destination)| destination)
+			.unwrap_or_else(|| self.destination.join(relative_path))
+	}
+
+	// decides, which operation will be performed for the entry, based on the overwrite policy.
+	fn plan<R: io::Read>(&self, entry: &Entry<'_, R>, extracted: &mut ExtractedEntry, planned: &HashSet<PathBuf>) -> Result<()> {
+		let existing = match fs::symlink_metadata(&extracted.destination) {
+			Ok(x) => x,
+			Err(_) if planned.contains(&extracted.destination) => {
+				// a dry-run: an earlier entry would have created the destination.
+				extracted.operation = match self.overwrite {
+					OverwritePolicy::Never => ExtractOperation::Skip(SkipReason::Exists),
+					OverwritePolicy::Rename => self.rename(extracted, planned),
+					_ => ExtractOperation::Overwrite,
+				};
+				return Ok(())
+			},
+			Err(_) => return Ok(()),
+		};
+		// existing directories will always be reused.
+		if existing.is_dir() && extracted.entry_type.is_dir() {
+			extracted.operation = ExtractOperation::Overwrite;
+			return Ok(())
+		}
+		extracted.operation = match self.overwrite {
+			OverwritePolicy::Never => ExtractOperation::Skip(SkipReason::Exists),
+			OverwritePolicy::Always => ExtractOperation::Overwrite,
+			OverwritePolicy::Rename => self.rename(extracted, planned),
+			OverwritePolicy::IfNewer => {
+				let entry_mtime = entry.header().mtime().map_err(|e| extract_error(&extracted.destination, e))?;
+				let existing_mtime = FileTime::from_last_modification_time(&existing).unix_seconds();
+				if entry_mtime as i64 > existing_mtime {
+					ExtractOperation::Overwrite
+				} else {
+					ExtractOperation::Skip(SkipReason::NotNewer)
+				}
+			},
+		};
+		if extracted.operation == ExtractOperation::Overwrite && existing.is_dir() {
+			// a directory can not be replaced by a file (or a link).
+			extracted.operation = ExtractOperation::Skip(SkipReason::Exists);
+		}
+		Ok(())
+	}
+
+	fn rename(&self, extracted: &mut ExtractedEntry, planned: &HashSet<PathBuf>) -> ExtractOperation {
+		let mut n = 1u64;
+		loop {
+			let mut name = extracted.destination.clone().into_os_string();
+			name.push(format!(".{}", n));
+			let candidate = PathBuf::from(name);
+			if fs::symlink_metadata(&candidate).is_err() && !planned.contains(&candidate) {
+				extracted.destination = candidate;
+				return ExtractOperation::Rename;
+			}
+			n += 1;
+		}
+	}
+
+	fn unpack<R: io::Read>(&self, entry: &mut Entry<'_, R>, extracted: &ExtractedEntry, link_target: Option<PathBuf>) -> Result<()> {
+		let destination = &extracted.destination;
+		create_parent_directories(&self.destination, destination)?;
+		if let Some(target) = link_target {
+			check_parent_directories(&self.destination, &target)?;
+			if extracted.operation == ExtractOperation::Overwrite {
+				fs::remove_file(destination).map_err(|e| extract_error(destination, e))?;
+			}
+			return fs::hard_link(target, destination).map_err(|e| extract_error(destination, e));
+		}
+		entry.unpack(destination).map_err(|e| extract_error(destination, e))?;
+		if self.restore_ownership && is_root() {
+			restore_ownership(entry, destination)?;
+		}
+		Ok(())
+	}
+}
+
+/// The options for [sync_directory].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncOptions {
+	/// deletes all files and directories, which are not part of the archive (default: true).
+	pub delete_extraneous: bool,
+}
+
+impl Default for SyncOptions {
+	fn default() -> SyncOptions {
+		SyncOptions {
+			delete_extraneous: true,
+		}
+	}
+}
+
+/// The summary of [sync_directory]. All paths are relative to the target directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+	/// the files, directories and links, which did not exist before.
+	pub created: Vec<PathBuf>,
+	/// the existing files, directories and links, which were replaced.
+	pub updated: Vec<PathBuf>,
+	/// the existing files, directories and links, which were left alone.
+	pub unchanged: Vec<PathBuf>,
+	/// the files and directories, which were deleted as they are not part of the archive.
+	pub deleted: Vec<PathBuf>,
+	/// the entries with unsafe paths or unsupported types (see [ArchiveExtractor]).
+	pub skipped: Vec<PathBuf>,
+}
+
+// the result of syncing a single entry.
+enum SyncStatus {
+	Created,
+	Updated,
+	Unchanged,
+}
+
+/// makes the target directory match the contents of the (gzip or zstd compressed, see [auto_decompress]) tar archive.
+/// Existing files with the same size and [HashExt::sha256sum] as the entry will be left alone, changed and new entries
+/// will be written (including their permissions and mtime). Files and directories, which are not part of the archive,
+/// will be deleted (see [SyncOptions::delete_extraneous]).
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use tar::Builder;
+/// use std::fs;
+/// use std::path::PathBuf;
+///
+/// fn main() -> Result<()> {
+/// 	let mut archive = Vec::new();
+/// 	let mut b = Builder::new(&mut archive);
+/// 	b.append_text("unchanged.txt", "unchanged")?;
+/// 	b.append_text("changed.txt", "new content")?;
+/// 	b.append_text("new.txt", "new")?;
+/// 	b.close_archive()?;
+///
+/// 	let target = std::env::temp_dir().join("phollaits_sync_example");
+/// 	let _ = fs::remove_dir_all(&target);
+/// 	fs::create_dir_all(&target).unwrap();
+/// 	fs::write(target.join("unchanged.txt"), "unchanged").unwrap();
+/// 	fs::write(target.join("changed.txt"), "old content").unwrap();
+/// 	fs::write(target.join("extraneous.txt"), "extraneous").unwrap();
+///
+/// 	let summary = sync_directory(&archive[..], &target, &SyncOptions::default())?;
+/// 	assert_eq!(summary.unchanged, vec![PathBuf::from("unchanged.txt")]);
+/// 	assert_eq!(summary.updated, vec![PathBuf::from("changed.txt")]);
+/// 	assert_eq!(summary.created, vec![PathBuf::from("new.txt")]);
+/// 	assert_eq!(summary.deleted, vec![PathBuf::from("extraneous.txt")]);
+/// 	assert_eq!(fs::read_to_string(target.join("changed.txt")).unwrap(), "new content");
+/// 	fs::remove_dir_all(&target).unwrap();
+/// 	Ok(())
+/// }
+/// ```
+pub fn sync_directory<R: io::Read, P: AsRef<Path>>(reader: R, target: P, options: &SyncOptions) -> Result<SyncSummary> {
+	let target = target.as_ref();
+	fs::create_dir_all(target).map_err(|e| extract_error(target, e))?;
+	let mut archive = Archive::new(auto_decompress(reader)?);
+	archive.set_overwrite(true);
+	let mut summary = SyncSummary::default();
+	// all paths of the archive, including the implicit parent directories.
+	let mut archive_paths = BTreeSet::new();
+	let entries = archive.entries().map_err(|e| extract_error(target, e))?;
+	for entry in entries {
+		let mut entry = entry.map_err(|e| extract_error(target, e))?;
+		let path = entry.path().map_err(|e| extract_error(target, e))?.into_owned();
+		let relative_path = match sanitize_path(&path) {
+			Some(x) if is_supported(entry.header().entry_type()) => x,
+			_ => {
+				summary.skipped.push(path);
+				continue;
+			}
+		};
+		for ancestor in relative_path.ancestors().filter(|x| !x.as_os_str().is_empty()) {
+			archive_paths.insert(ancestor.to_path_buf());
+		}
+		let destination = target.join(&relative_path);
+		create_parent_directories(target, &destination)?;
+		match sync_entry(&mut entry, target, &destination)? {
+			SyncStatus::Created => summary.created.push(relative_path),
+			SyncStatus::Updated => summary.updated.push(relative_path),
+			SyncStatus::Unchanged => summary.unchanged.push(relative_path),
+		}
+	}
+	if options.delete_extraneous {
+		delete_extraneous(target, Path::new(""), &archive_paths, &mut summary.deleted)?;
+	}
+	Ok(summary)
+}
+
+fn sync_entry<R: io::Read>(entry: &mut Entry<'_, R>, target: &Path, destination: &Path) -> Result<SyncStatus> {
+	let entry_type = entry.header().entry_type();
+	let existing = match fs::symlink_metadata(destination) {
+		Ok(x) => x,
+		Err(_) => {
+			if entry_type.is_hard_link() {
+				sync_hard_link(entry, target, destination)?;
+			} else {
+				entry.unpack(destination).map_err(|e| extract_error(destination, e))?;
+			}
+			return Ok(SyncStatus::Created)
+		}
+	};
+	if entry_type.is_dir() {
+		if existing.is_dir() {
+			return Ok(SyncStatus::Unchanged)
+		}
+		remove_path(destination, &existing)?;
+		entry.unpack(destination).map_err(|e| extract_error(destination, e))?;
+	} else if entry_type.is_symlink() {
+		let link_name = entry.link_name().map_err(|e| extract_error(destination, e))?;
+		if existing.file_type().is_symlink() && fs::read_link(destination).ok().as_deref() == link_name.as_deref() {
+			return Ok(SyncStatus::Unchanged)
+		}
+		remove_path(destination, &existing)?;
+		entry.unpack(destination).map_err(|e| extract_error(destination, e))?;
+	} else if entry_type.is_hard_link() {
+		let link_target = hard_link_target(entry, target, destination)?;
+		check_parent_directories(target, &link_target)?;
+		if existing.is_file() && same_content(&link_target, destination)? {
+			return Ok(SyncStatus::Unchanged)
+		}
+		remove_path(destination, &existing)?;
+		sync_hard_link(entry, target, destination)?;
+	} else if existing.is_file() && existing.len() == entry.size() {
+		// the entry can only be read once, so it will be written to a temporary file for the comparison.
+		let temporary = temporary_path(destination);
+		let result = entry.unpack(&temporary).map_err(|e| extract_error(&temporary, e))
+			.and_then(|_| same_content(&temporary, destination));
+		match result {
+			Ok(true) => {
+				fs::remove_file(&temporary).map_err(|e| extract_error(&temporary, e))?;
+				return Ok(SyncStatus::Unchanged)
+			},
+			Ok(false) => fs::rename(&temporary, destination).map_err(|e| extract_error(destination, e))?,
+			Err(e) => {
+				let _ = fs::remove_file(&temporary);
+				return Err(e);
+			},
+		}
+	} else {
+		remove_path(destination, &existing)?;
+		entry.unpack(destination).map_err(|e| extract_error(destination, e))?;
+	}
+	Ok(SyncStatus::Updated)
+}
+
+// returns an unused path next to the destination, for a temporary copy of an entry.
+fn temporary_path(destination: &Path) -> PathBuf {
+	static COUNTER: AtomicUsize = AtomicUsize::new(0);
+	loop {
+		let mut temporary = destination.as_os_str().to_owned();
+		temporary.push(format!(".phollaits-sync.{}.{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+		let temporary = PathBuf::from(temporary);
+		if fs::symlink_metadata(&temporary).is_err() {
+			return temporary;
+		}
+	}
+}
+
+fn hard_link_target<R: io::Read>(entry: &Entry<'_, R>, target: &Path, destination: &Path) -> Result<PathBuf> {
+	let link_name = entry.link_name().map_err(|e| extract_error(destination, e))?;
+	match link_name.as_ref().and_then(|x| sanitize_path(x)) {
+		Some(x) => Ok(target.join(x)),
+		None => Err(PhollaitsError::new(
+			PhollaitsErrorKind::ArchiveError,
+			format!("Error while trying to extract {}; invalid hardlink target", destination.display())))
+	}
+}
+
+fn sync_hard_link<R: io::Read>(entry: &Entry<'_, R>, target: &Path, destination: &Path) -> Result<()> {
+	let link_target = hard_link_target(entry, target, destination)?;
+	check_parent_directories(target, &link_target)?;
+	fs::hard_link(link_target, destination).map_err(|e| extract_error(destination, e))
+}
+
+// compares the size and the sha256 digest of both files.
+fn same_content(first: &Path, second: &Path) -> Result<bool> {
+	let first_size = fs::metadata(first).map_err(|e| extract_error(first, e))?.len();
+	let second_size = fs::metadata(second).map_err(|e| extract_error(second, e))?.len();
+	if first_size != second_size {
+		return Ok(false)
+	}
+	let first = fs::File::open(first).map_err(|e| extract_error(first, e))?.sha256sum()?;
+	let second = fs::File::open(second).map_err(|e| extract_error(second, e))?.sha256sum()?;
+	Ok(first == second)
+}
+
+fn remove_path(path: &Path, metadata: &fs::Metadata) -> Result<()> {
+	let result = if metadata.is_dir() {
+		fs::remove_dir_all(path)
+	} else {
+		fs::remove_file(path)
+	};
+	result.map_err(|e| extract_error(path, e))
+}
+
+// deletes all files and directories below the target, which are not part of the archive (without following symlinks).
+fn delete_extraneous(target: &Path, relative_directory: &Path, archive_paths: &BTreeSet<PathBuf>, deleted: &mut Vec<PathBuf>) -> Result<()> {
+	let directory = target.join(relative_directory);
+	let mut children = Vec::new();
+	for child in fs::read_dir(&directory).map_err(|e| extract_error(&directory, e))? {
+		children.push(child.map_err(|e| extract_error(&directory, e))?);
+	}
+	children.sort_by_key(|x| x.file_name());
+	for child in children {
+		let relative_path = relative_directory.join(child.file_name());
+		let metadata = fs::symlink_metadata(child.path()).map_err(|e| extract_error(child.path(), e))?;
+		if !archive_paths.contains(&relative_path) {
+			remove_path(&child.path(), &metadata)?;
+			deleted.push(relative_path);
+		} else if metadata.is_dir() {
+			delete_extraneous(target, &relative_path, archive_paths, deleted)?;
+		}
+	}
+	Ok(())
+}
+
+// creates the parent directories of the destination (below the root directory). Every existing parent has to be a
+// real directory: a symlink could point outside of the root directory, so nothing will be created below it.
+fn create_parent_directories(root: &Path, destination: &Path) -> Result<()> {
+	walk_parent_directories(root, destination, true)
+}
+
+// ensures, that all parents of the path (below the root directory) are real directories (e.g. for hardlink targets).
+fn check_parent_directories(root: &Path, path: &Path) -> Result<()> {
+	walk_parent_directories(root, path, false)
+}
+
+fn walk_parent_directories(root: &Path, destination: &Path, create: bool) -> Result<()> {
+	let relative_parent = match destination.strip_prefix(root).ok().and_then(|x| x.parent()) {
+		Some(x) => x,
+		None => return Ok(()),
+	};
+	let mut parent = root.to_path_buf();
+	for component in relative_parent.components() {
+		parent.push(component);
+		match fs::symlink_metadata(&parent) {
+			Ok(metadata) if metadata.is_dir() => continue,
+			Ok(_) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Error while trying to extract {}; {} is not a directory", destination.display(), parent.display()))),
+			Err(_) if create => fs::create_dir(&parent).map_err(|e| extract_error(&parent, e))?,
+			Err(e) => return Err(extract_error(&parent, e)),
+		}
+	}
+	Ok(())
+}
+
+fn is_supported(entry_type: EntryType) -> bool {
+	matches!(entry_type,
+		EntryType::Regular | EntryType::Continuous | EntryType::GNUSparse |
+		EntryType::Directory | EntryType::Symlink | EntryType::Link)
+}
+
+// removes the root and "." components; returns None for paths with ".." components.
+fn sanitize_path(path: &Path) -> Option<PathBuf> {
+	let mut sanitized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::Normal(x) => sanitized.push(x),
+			Component::ParentDir => return None,
+			_ => (),
+		}
+	}
+	if sanitized.as_os_str().is_empty() {
+		None
+	} else {
+		Some(sanitized)
+	}
+}
+
+// returns the (atime, mtime) of the entry. The atime will be read from the GNU header or the PAX extensions, if available,
+// otherwise the mtime will be used.
+fn entry_times<R: io::Read>(entry: &mut Entry<'_, R>) -> Option<(FileTime, FileTime)> {
+	let mtime = FileTime::from_unix_time(entry.header().mtime().ok()? as i64, 0);
+	let mut atime = entry.header().as_gnu().and_then(|gnu| gnu.atime().ok())
+		.filter(|atime| *atime != 0)
+		.map(|atime| FileTime::from_unix_time(atime as i64, 0));
+	if let Ok(Some(extensions)) = entry.pax_extensions() {
+		for extension in extensions.flatten() {
+			if extension.key() == Ok("atime") {
+				if let Some(time) = extension.value().ok().and_then(parse_pax_time) {
+					atime = Some(time);
+				}
+			}
+		}
+	}
+	Some((atime.unwrap_or(mtime), mtime))
+}
+
+// parses a PAX timestamp, like "1623246000.123456789".
+fn parse_pax_time(value: &str) -> Option<FileTime> {
+	let mut parts = value.splitn(2, '.');
+	let seconds = parts.next()?.parse::<i64>().ok()?;
+	let nanos = match parts.next() {
+		Some(fraction) => {
+			let digits: String = fraction.chars().take(9).collect();
+			format!("{:0<9}", digits).parse::<u32>().ok()?
+		},
+		None => 0,
+	};
+	Some(FileTime::from_unix_time(seconds, nanos))
+}
+
+fn set_times(destination: &Path, entry_type: EntryType, (atime, mtime): (FileTime, FileTime)) -> Result<()> {
+	let result = if entry_type.is_symlink() {
+		filetime::set_symlink_file_times(destination, atime, mtime)
+	} else {
+		filetime::set_file_times(destination, atime, mtime)
+	};
+	result.map_err(|e| extract_error(destination, e))
+}
+
+// restores the ownership, if the header contains a valid uid and gid (e.g. [TarBuilderExt::append_text] leaves them
+// empty). Changing the owner clears the setuid and setgid bits, so the permissions will be restored afterwards.
+#[cfg(unix)]
+fn restore_ownership<R: io::Read>(entry: &Entry<'_, R>, destination: &Path) -> Result<()> {
+	use std::os::unix::fs::PermissionsExt;
+
+	let header = entry.header();
+	let (uid, gid) = match (header.uid(), header.gid()) {
+		(Ok(uid), Ok(gid)) => (uid as u32, gid as u32),
+		_ => return Ok(()),
+	};
+	let mode = fs::symlink_metadata(destination).map_err(|e| extract_error(destination, e))?.permissions().mode();
+	std::os::unix::fs::lchown(destination, Some(uid), Some(gid)).map_err(|e| extract_error(destination, e))?;
+	if header.entry_type().is_symlink() {
+		return Ok(())
+	}
+	fs::set_permissions(destination, fs::Permissions::from_mode(mode)).map_err(|e| extract_error(destination, e))
+}
+
+#[cfg(not(unix))]
+fn restore_ownership<R: io::Read>(_entry: &Entry<'_, R>, _destination: &Path) -> Result<()> {
+	Ok(())
+}
+
+#[cfg(unix)]
+fn is_root() -> bool {
+	unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_root() -> bool {
+	false
+}
+
+fn extract_error<P: AsRef<Path>>(path: P, e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::ArchiveError,
+		format!("Error while trying to extract {}; {}", path.as_ref().display(), e))
+}
@@ -0,0 +1,425 @@
+/*************************************************************************
+* ph0llux:93d0b5e7a2c14f86d1e3b07a5c92f4e81d6a0c3b57e29f84a1c6d03b7e52a9f1
+*************************************************************************/
+//!fuzzy Module (context triggered piecewise hashing, compatible with ssdeep).
+
+// - STD
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+// - internal
+use super::{PhollaitsError, PhollaitsErrorKind, Result};
+#[cfg(doc)]
+use super::{HashDataExt, HashExt};
+
+const ROLLING_WINDOW: usize = 7;
+const MIN_BLOCKSIZE: u64 = 3;
+const HASH_PRIME: u32 = 0x0100_0193;
+const HASH_INIT: u32 = 0x2802_1967;
+const NUM_BLOCKHASHES: usize = 31;
+const SPAMSUM_LENGTH: usize = 64;
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A fuzzy hash (context triggered piecewise hash), in the format of ssdeep: "blocksize:hash:hash". The first hash uses
+/// the block size, the second hash the double block size. Use [HashExt::fuzzy_hash] or [HashDataExt::fuzzy_hash] to
+/// calculate a fuzzy hash and [FuzzyHash::compare] to compare two fuzzy hashes.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	assert_eq!("".fuzzy_hash()?.to_string(), "3::");
+///
+/// 	let hash = "The quick brown fox jumps over the lazy dog".fuzzy_hash()?;
+/// 	assert_eq!(hash.to_string(), "3:FJKKIUKact:FHIGi");
+///
+/// 	// the digest for the initial block size (24) is too short, so the block size is halved.
+/// 	let data: Vec<u8> = (0..1000u32).map(|i| (i * i % 251) as u8).collect();
+/// 	assert_eq!(data.fuzzy_hash()?.to_string(),
+/// 		"12:mneuwHyV+IZSyiJKXrsopneuwHyV+IZSyiJKXrsopneuwHyV+IZSyiJKXrsopnez:+F+ITQIF+ITQIF+ITQIF+ITs");
+///
+/// 	let parsed: FuzzyHash = "3:FJKKIUKact:FHIGi,\"fox.txt\"".parse()?;
+/// 	assert_eq!(parsed.block_size(), 3);
+/// 	assert_eq!(parsed.first(), "FJKKIUKact");
+/// 	assert_eq!(parsed.compare(&hash), 100);
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FuzzyHash {
+	block_size: u64,
+	first: String,
+	second: String,
+}
+
+impl FuzzyHash {
+	/// returns the block size of the first hash.
+	pub fn block_size(&self) -> u64 {
+		self.block_size
+	}
+
+	/// returns the hash for the block size.
+	pub fn first(&self) -> &str {
+		&self.first
+	}
+
+	/// returns the hash for the double block size.
+	pub fn second(&self) -> &str {
+		&self.second
+	}
+
+	/// compares two fuzzy hashes like ssdeep and returns a score between 0 (no similarity) and 100 (a very similar or
+	/// identical input). Only hashes with the same or with a double block size can be compared, otherwise the score is 0.
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::*;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let data = std::fs::read("assets/example.jpg").unwrap();
+	/// 	let mut modified = data.clone();
+	/// 	modified[40000..40100].copy_from_slice(&[0; 100]);
+	/// 	let score = data.fuzzy_hash()?.compare(&modified.fuzzy_hash()?);
+	/// 	assert!(score > 80 && score < 100);
+	///
+	/// 	let other = std::fs::read("assets/textfile.txt").unwrap();
+	/// 	assert_eq!(data.fuzzy_hash()?.compare(&other.fuzzy_hash()?), 0);
+	///
+	/// 	let first: FuzzyHash = "9223372036854775808:AAAAAAAB:AAAA".parse()?;
+	/// 	let second: FuzzyHash = "9223372036854775808:AAAAAAAC:AAAA".parse()?;
+	/// 	assert!(first.compare(&second) < 100);
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn compare(&self, other: &FuzzyHash) -> u32 {
+		let (first, second) = (self, other);
+		if first.block_size != second.block_size
+			&& first.block_size.checked_mul(2) != Some(second.block_size)
+			&& second.block_size.checked_mul(2) != Some(first.block_size) {
+			return 0;
+		}
+		// runs of more than three identical characters carry almost no information.
+		let first_hashes = (eliminate_sequences(&first.first), eliminate_sequences(&first.second));
+		let second_hashes = (eliminate_sequences(&second.first), eliminate_sequences(&second.second));
+		if first.block_size == second.block_size && first_hashes == second_hashes {
+			return 100;
+		}
+		if first.block_size == second.block_size {
+			let score1 = score_strings(&first_hashes.0, &second_hashes.0, first.block_size);
+			let score2 = score_strings(&first_hashes.1, &second_hashes.1, first.block_size.saturating_mul(2));
+			score1.max(score2)
+		} else if first.block_size.checked_mul(2) == Some(second.block_size) {
+			score_strings(&second_hashes.0, &first_hashes.1, second.block_size)
+		} else {
+			score_strings(&first_hashes.0, &second_hashes.1, first.block_size)
+		}
+	}
+}
+
+impl fmt::Display for FuzzyHash {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}:{}", self.block_size, self.first, self.second)
+	}
+}
+
+impl FromStr for FuzzyHash {
+	type Err = PhollaitsError;
+
+	/// parses a fuzzy hash. A trailing file name (like in the output of ssdeep: "hash,\"filename\"") will be ignored.
+	fn from_str(s: &str) -> Result<FuzzyHash> {
+		let mut parts = s.trim().splitn(3, ':');
+		let (block_size, first, second) = match (parts.next(), parts.next(), parts.next()) {
+			(Some(block_size), Some(first), Some(second)) => (block_size, first, second),
+			_ => return Err(invalid_fuzzy_hash(s)),
+		};
+		let second = second.split(',').next().unwrap_or_default();
+		let block_size: u64 = block_size.parse()?;
+		let is_valid = |hash: &str| hash.len() <= SPAMSUM_LENGTH && hash.bytes().all(|byte| BASE64_ALPHABET.contains(&byte));
+		if block_size < MIN_BLOCKSIZE || !is_valid(first) || !is_valid(second) {
+			return Err(invalid_fuzzy_hash(s));
+		}
+		Ok(FuzzyHash {
+			block_size,
+			first: first.to_string(),
+			second: second.to_string(),
+		})
+	}
+}
+
+/// A streaming calculator of [FuzzyHash]es. The input can be fed in chunks with [FuzzyHasher::update].
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let data = std::fs::read("assets/example.jpg").unwrap();
+/// 	let mut hasher = FuzzyHasher::new();
+/// 	for chunk in data.chunks(1000) {
+/// 		hasher.update(chunk);
+/// 	}
+/// 	assert_eq!(hasher.finalize(), data.fuzzy_hash()?);
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FuzzyHasher {
+	block_hashes: Vec<BlockHash>,
+	// the index of the smallest block size, which is still of interest.
+	start: usize,
+	total_size: u64,
+	roll: RollingHash,
+	// the hash of the largest block size, once all block hashes are in use.
+	last_hash: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+struct BlockHash {
+	hash: u32,
+	half_hash: u32,
+	digest: Vec<u8>,
+	// the last character, once the digest is full.
+	tail: Option<u8>,
+	half_digest: Option<u8>,
+}
+
+impl BlockHash {
+	fn new(hash: u32, half_hash: u32) -> BlockHash {
+		BlockHash {
+			hash,
+			half_hash,
+			digest: Vec::with_capacity(SPAMSUM_LENGTH),
+			tail: None,
+			half_digest: None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+struct RollingHash {
+	window: [u8; ROLLING_WINDOW],
+	h1: u32,
+	h2: u32,
+	h3: u32,
+	n: usize,
+}
+
+impl RollingHash {
+	fn update(&mut self, byte: u8) {
+		let byte = u32::from(byte);
+		self.h2 = self.h2.wrapping_sub(self.h1).wrapping_add(ROLLING_WINDOW as u32 * byte);
+		self.h1 = self.h1.wrapping_add(byte).wrapping_sub(u32::from(self.window[self.n % ROLLING_WINDOW]));
+		self.window[self.n % ROLLING_WINDOW] = byte as u8;
+		self.n = (self.n + 1) % ROLLING_WINDOW;
+		self.h3 = (self.h3 << 5) ^ byte;
+	}
+
+	fn sum(&self) -> u32 {
+		self.h1.wrapping_add(self.h2).wrapping_add(self.h3)
+	}
+}
+
+impl Default for FuzzyHasher {
+	fn default() -> FuzzyHasher {
+		FuzzyHasher::new()
+	}
+}
+
+impl FuzzyHasher {
+	/// returns a new hasher.
+	pub fn new() -> FuzzyHasher {
+		FuzzyHasher {
+			block_hashes: vec![BlockHash::new(HASH_INIT, HASH_INIT)],
+			start: 0,
+			total_size: 0,
+			roll: RollingHash::default(),
+			last_hash: None,
+		}
+	}
+
+	/// feeds the data into the hasher.
+	pub fn update(&mut self, data: &[u8]) {
+		self.total_size += data.len() as u64;
+		for byte in data {
+			self.step(*byte);
+		}
+	}
+
+	/// returns the fuzzy hash of the input.
+	pub fn finalize(&self) -> FuzzyHash {
+		let roll_sum = self.roll.sum();
+		let mut index = self.start;
+		// the initial guess of the block size, adapted to the actual length of the hashes.
+		while block_size(index) * (SPAMSUM_LENGTH as u64) < self.total_size && index < NUM_BLOCKHASHES - 1 {
+			index += 1;
+		}
+		index = index.min(self.block_hashes.len() - 1);
+		while index > self.start && self.block_hashes[index].digest.len() < SPAMSUM_LENGTH / 2 {
+			index -= 1;
+		}
+
+		let block_hash = &self.block_hashes[index];
+		let mut first = block_hash.digest.clone();
+		if roll_sum != 0 {
+			first.push(base64(block_hash.hash));
+		} else if let Some(tail) = block_hash.tail {
+			first.push(tail);
+		}
+
+		let mut second = Vec::new();
+		if let Some(block_hash) = self.block_hashes.get(index + 1) {
+			second.extend_from_slice(&block_hash.digest[..block_hash.digest.len().min(SPAMSUM_LENGTH / 2 - 1)]);
+			if roll_sum != 0 {
+				second.push(base64(block_hash.half_hash));
+			} else if let Some(half_digest) = block_hash.half_digest {
+				second.push(half_digest);
+			}
+		} else if roll_sum != 0 {
+			match self.last_hash {
+				Some(last_hash) if index == NUM_BLOCKHASHES - 1 => second.push(base64(last_hash)),
+				_ => second.push(base64(block_hash.hash)),
+			}
+		}
+
+		FuzzyHash {
+			block_size: block_size(index),
+			first: String::from_utf8_lossy(&first).into_owned(),
+			second: String::from_utf8_lossy(&second).into_owned(),
+		}
+	}
+
+	fn step(&mut self, byte: u8) {
+		self.roll.update(byte);
+		let roll_sum = self.roll.sum();
+		for block_hash in &mut self.block_hashes[self.start..] {
+			block_hash.hash = sum_hash(byte, block_hash.hash);
+			block_hash.half_hash = sum_hash(byte, block_hash.half_hash);
+		}
+		if let Some(last_hash) = self.last_hash.as_mut() {
+			*last_hash = sum_hash(byte, *last_hash);
+		}
+
+		// a trigger point for a block size is also a trigger point for all smaller block sizes.
+		if u64::from(roll_sum) % MIN_BLOCKSIZE != MIN_BLOCKSIZE - 1 {
+			return;
+		}
+		let mut index = self.start;
+		while index < self.block_hashes.len() {
+			if u64::from(roll_sum) % block_size(index) != block_size(index) - 1 {
+				break;
+			}
+			// the next block size is not needed until the first trigger point of this block size.
+			if self.block_hashes[index].digest.is_empty() {
+				self.fork_block_hash();
+			}
+			let block_hash = &mut self.block_hashes[index];
+			let character = base64(block_hash.hash);
+			block_hash.half_digest = Some(base64(block_hash.half_hash));
+			if block_hash.digest.len() < SPAMSUM_LENGTH - 1 {
+				block_hash.digest.push(character);
+				block_hash.hash = HASH_INIT;
+				if block_hash.digest.len() < SPAMSUM_LENGTH / 2 {
+					block_hash.half_hash = HASH_INIT;
+					block_hash.half_digest = None;
+				}
+			} else {
+				// the digest is full, so the remaining input will be combined into the last character.
+				block_hash.tail = Some(character);
+				self.try_reduce_block_hash();
+			}
+			index += 1;
+		}
+	}
+
+	fn fork_block_hash(&mut self) {
+		let last = &self.block_hashes[self.block_hashes.len() - 1];
+		if self.block_hashes.len() < NUM_BLOCKHASHES {
+			let block_hash = BlockHash::new(last.hash, last.half_hash);
+			self.block_hashes.push(block_hash);
+		} else if self.last_hash.is_none() {
+			self.last_hash = Some(last.hash);
+		}
+	}
+
+	// drops the smallest block size, if it will not be used by the hash anymore.
+	fn try_reduce_block_hash(&mut self) {
+		if self.block_hashes.len() - self.start < 2
+			|| block_size(self.start) * (SPAMSUM_LENGTH as u64) >= self.total_size
+			|| self.block_hashes[self.start + 1].digest.len() < SPAMSUM_LENGTH / 2 {
+			return;
+		}
+		self.start += 1;
+	}
+}
+
+fn block_size(index: usize) -> u64 {
+	MIN_BLOCKSIZE << index
+}
+
+fn sum_hash(byte: u8, hash: u32) -> u32 {
+	hash.wrapping_mul(HASH_PRIME) ^ u32::from(byte)
+}
+
+fn base64(hash: u32) -> u8 {
+	BASE64_ALPHABET[(hash % 64) as usize]
+}
+
+fn eliminate_sequences(hash: &str) -> Vec<u8> {
+	let mut result: Vec<u8> = Vec::with_capacity(hash.len());
+	for byte in hash.bytes() {
+		let len = result.len();
+		if len >= 3 && result[len - 1] == byte && result[len - 2] == byte && result[len - 3] == byte {
+			continue;
+		}
+		result.push(byte);
+	}
+	result
+}
+
+fn score_strings(first: &[u8], second: &[u8], block_size: u64) -> u32 {
+	if first.len() > SPAMSUM_LENGTH || second.len() > SPAMSUM_LENGTH || !has_common_substring(first, second) {
+		return 0;
+	}
+	// the edit distance, scaled by the lengths of the strings and rescaled to 0 (no match) - 100 (perfect match).
+	let distance = edit_distance(first, second) as u64;
+	let mut score = distance * SPAMSUM_LENGTH as u64 / (first.len() + second.len()) as u64;
+	score = 100 - (100 * score) / SPAMSUM_LENGTH as u64;
+	// the match of small block sizes should not be exaggerated.
+	let min_len = first.len().min(second.len()) as u64;
+	if block_size < (99 + ROLLING_WINDOW as u64) / ROLLING_WINDOW as u64 * MIN_BLOCKSIZE {
+		score = score.min(block_size / MIN_BLOCKSIZE * min_len);
+	}
+	score as u32
+}
+
+// both strings must have a common substring of the length of the rolling window.
+fn has_common_substring(first: &[u8], second: &[u8]) -> bool {
+	if first.len() < ROLLING_WINDOW || second.len() < ROLLING_WINDOW {
+		return false;
+	}
+	let windows: HashSet<&[u8]> = first.windows(ROLLING_WINDOW).collect();
+	second.windows(ROLLING_WINDOW).any(|window| windows.contains(window))
+}
+
+// the edit distance, where insertions and deletions cost 1 and substitutions cost 2.
+fn edit_distance(first: &[u8], second: &[u8]) -> usize {
+	let mut previous: Vec<usize> = (0..=second.len()).collect();
+	let mut current = vec![0; second.len() + 1];
+	for (i, first_byte) in first.iter().enumerate() {
+		current[0] = i + 1;
+		for (j, second_byte) in second.iter().enumerate() {
+			let substitution = previous[j] + if first_byte == second_byte { 0 } else { 2 };
+			current[j + 1] = (previous[j + 1] + 1).min(current[j] + 1).min(substitution);
+		}
+		std::mem::swap(&mut previous, &mut current);
+	}
+	previous[second.len()]
+}
+
+fn invalid_fuzzy_hash(hash: &str) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to parse fuzzy hash {}; invalid format", hash))
+}
@@ -4,27 +4,356 @@
 //!hash Module.
 
 // - STD
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::hash::Hash;
 use std::io;
-use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 // - internal
-use super::{PhollaitsError, PhollaitsErrorKind, Result};
+use super::{FuzzyHash, FuzzyHasher, HashPiece, PhollaitsError, PhollaitsErrorKind, PiecewiseHash, Result};
 
 // - external
-use data_encoding::HEXLOWER;
+use data_encoding::{HEXLOWER, HEXLOWER_PERMISSIVE, HEXUPPER};
 use md5::{Md5};
 use sha1::{Sha1};
 use sha2::{Sha256, Sha384, Sha512};
-use tar::Entry;
+#[cfg(feature = "sha3")]
+use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512};
+#[cfg(feature = "blake2")]
+use blake2::{Blake2b, Blake2s};
 
-/// This trait implements several hash-algorithms for several types.
+/// The default size (in bytes) of the buffer, which is used to read the input of [HashExt]. Use e.g.
+/// [HashExt::hash_with_buffer_size] for a different size.
+pub const DEFAULT_HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// The hash algorithms, which are supported by [HashExt]. SHA-3, BLAKE2 and BLAKE3 are only available with the
+/// features "sha3", "blake2" and "blake3".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HashAlgorithm {
+	Md5,
+	Sha1,
+	Sha256,
+	Sha384,
+	Sha512,
+	#[cfg(feature = "sha3")]
+	Sha3_224,
+	#[cfg(feature = "sha3")]
+	Sha3_256,
+	#[cfg(feature = "sha3")]
+	Sha3_384,
+	#[cfg(feature = "sha3")]
+	Sha3_512,
+	/// BLAKE2b with a 512 bit digest.
+	#[cfg(feature = "blake2")]
+	Blake2b,
+	/// BLAKE2s with a 256 bit digest.
+	#[cfg(feature = "blake2")]
+	Blake2s,
+	/// BLAKE3 with the default 256 bit digest.
+	#[cfg(feature = "blake3")]
+	Blake3,
+}
+
+impl HashAlgorithm {
+	/// returns all supported algorithms.
+	pub fn all() -> &'static [HashAlgorithm] {
+		&[
+			HashAlgorithm::Md5,
+			HashAlgorithm::Sha1,
+			HashAlgorithm::Sha256,
+			HashAlgorithm::Sha384,
+			HashAlgorithm::Sha512,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_224,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_256,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_384,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_512,
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2b,
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2s,
+			#[cfg(feature = "blake3")]
+			HashAlgorithm::Blake3,
+		]
+	}
+
+	/// returns the (lowercase) name of the algorithm, e.g. "sha256".
+	pub fn name(&self) -> &'static str {
+		match self {
+			HashAlgorithm::Md5 => "md5",
+			HashAlgorithm::Sha1 => "sha1",
+			HashAlgorithm::Sha256 => "sha256",
+			HashAlgorithm::Sha384 => "sha384",
+			HashAlgorithm::Sha512 => "sha512",
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_224 => "sha3-224",
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_256 => "sha3-256",
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_384 => "sha3-384",
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_512 => "sha3-512",
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2b => "blake2b",
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2s => "blake2s",
+			#[cfg(feature = "blake3")]
+			HashAlgorithm::Blake3 => "blake3",
+		}
+	}
+
+	/// returns the length of the digest in bytes (the hex representation is twice as long).
+	pub fn digest_len(&self) -> usize {
+		match self {
+			HashAlgorithm::Md5 => 16,
+			HashAlgorithm::Sha1 => 20,
+			HashAlgorithm::Sha256 => 32,
+			HashAlgorithm::Sha384 => 48,
+			HashAlgorithm::Sha512 => 64,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_224 => 28,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_256 => 32,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_384 => 48,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_512 => 64,
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2b => 64,
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2s => 32,
+			#[cfg(feature = "blake3")]
+			HashAlgorithm::Blake3 => 32,
+		}
+	}
+
+	/// returns the internal block length in bytes (e.g. used by HMAC, see [Mac]).
+	pub fn block_len(&self) -> usize {
+		match self {
+			HashAlgorithm::Md5 => 64,
+			HashAlgorithm::Sha1 => 64,
+			HashAlgorithm::Sha256 => 64,
+			HashAlgorithm::Sha384 => 128,
+			HashAlgorithm::Sha512 => 128,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_224 => 144,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_256 => 136,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_384 => 104,
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_512 => 72,
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2b => 128,
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2s => 64,
+			#[cfg(feature = "blake3")]
+			HashAlgorithm::Blake3 => 64,
+		}
+	}
+}
+
+impl fmt::Display for HashAlgorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.name())
+	}
+}
+
+/// parses the name of an algorithm. The parsing is case-insensitive and ignores "-" and "_", so e.g. "sha256",
+/// "SHA-256" and "Sha_256" (or "sha3-256" and "SHA3_256") are accepted.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::{HashAlgorithm, Result};
+///
+/// fn main() -> Result<()> {
+/// 	let algorithm: HashAlgorithm = "SHA-256".parse()?;
+/// 	assert_eq!(algorithm, HashAlgorithm::Sha256);
+/// 	assert_eq!(algorithm.to_string(), "sha256");
+/// 	assert_eq!(algorithm.digest_len(), 32);
+/// 	assert!("sha257".parse::<HashAlgorithm>().is_err());
+/// 	Ok(())
+/// }
+/// ```
+impl FromStr for HashAlgorithm {
+	type Err = PhollaitsError;
+
+	fn from_str(s: &str) -> Result<HashAlgorithm> {
+		let name = normalize_algorithm_name(s);
+		match HashAlgorithm::all().iter().find(|algorithm| normalize_algorithm_name(algorithm.name()) == name) {
+			Some(algorithm) => Ok(*algorithm),
+			None => Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Unknown hash algorithm {}", s)))
+		}
+	}
+}
+
+/// A digest, calculated by [HashExt] or [HashDataExt]: the algorithm and the raw bytes of the digest.
+/// Two digests are equal, if the algorithms and the bytes are equal; the bytes are compared in constant time.
+/// The [Display](fmt::Display) implementation renders the digest as lowercase hex, the alternate form ("{:#}") prefixes
+/// the name of the algorithm, like "sha256:5b12...". Both forms can be parsed by [FromStr].
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::{Digest, HashAlgorithm, HashExt, Result};
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+/// 	let digest = file.digest_with(HashAlgorithm::Md5)?;
+/// 	assert_eq!(digest.algorithm(), HashAlgorithm::Md5);
+/// 	assert_eq!(digest.to_hex(), "a4494bd1b83303bc0872a996e6c8a8bf");
+/// 	assert_eq!(digest.to_hex_upper(), "A4494BD1B83303BC0872A996E6C8A8BF");
+/// 	assert_eq!(digest.to_base64(), "pElL0bgzA7wIcqmW5siovw==");
+/// 	assert_eq!(format!("{:#}", digest), "md5:a4494bd1b83303bc0872a996e6c8a8bf");
+///
+/// 	assert_eq!(digest, Digest::from_hex(HashAlgorithm::Md5, "A4494BD1B83303BC0872A996E6C8A8BF")?);
+/// 	assert_eq!(digest, "md5:a4494bd1b83303bc0872a996e6c8a8bf".parse()?);
+/// 	// without a prefix, the algorithm will be derived from the length.
+/// 	assert_eq!(digest, "a4494bd1b83303bc0872a996e6c8a8bf".parse()?);
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Digest {
+	algorithm: HashAlgorithm,
+	bytes: Vec<u8>,
+}
+
+impl Digest {
+	/// returns a new digest. The length of the bytes has to match [HashAlgorithm::digest_len].
+	pub fn new<B: Into<Vec<u8>>>(algorithm: HashAlgorithm, bytes: B) -> Result<Digest> {
+		let bytes = bytes.into();
+		if bytes.len() != algorithm.digest_len() {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Invalid {} digest; expected {} bytes, got {}", algorithm, algorithm.digest_len(), bytes.len())))
+		}
+		Ok(Digest { algorithm, bytes })
+	}
+
+	// the length of the bytes has to be checked by the caller.
+	pub(crate) fn from_parts(algorithm: HashAlgorithm, bytes: Vec<u8>) -> Digest {
+		Digest { algorithm, bytes }
+	}
+
+	/// parses a (lowercase or uppercase) hex digest.
+	pub fn from_hex(algorithm: HashAlgorithm, hex: &str) -> Result<Digest> {
+		match HEXLOWER_PERMISSIVE.decode(hex.trim().as_bytes()) {
+			Ok(bytes) => Digest::new(algorithm, bytes),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Invalid hex digest {}; {}", hex, e)))
+		}
+	}
+
+	/// returns the algorithm of the digest.
+	pub fn algorithm(&self) -> HashAlgorithm {
+		self.algorithm
+	}
+
+	/// returns the raw bytes of the digest.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
+	}
+
+	/// returns the raw bytes of the digest.
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+
+	/// returns the digest as a lowercase hex [String].
+	pub fn to_hex(&self) -> String {
+		HEXLOWER.encode(&self.bytes)
+	}
+
+	/// returns the digest as an uppercase hex [String].
+	pub fn to_hex_upper(&self) -> String {
+		HEXUPPER.encode(&self.bytes)
+	}
+
+	/// returns the digest as a (standard, padded) base64 [String].
+	pub fn to_base64(&self) -> String {
+		base64::encode(&self.bytes)
+	}
+}
+
+impl PartialEq for Digest {
+	fn eq(&self, other: &Digest) -> bool {
+		self.algorithm == other.algorithm && constant_time_eq(&self.bytes, &other.bytes)
+	}
+}
+
+impl Eq for Digest {}
+
+impl Hash for Digest {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.algorithm.hash(state);
+		self.bytes.hash(state);
+	}
+}
+
+impl fmt::Debug for Digest {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Digest({}:{})", self.algorithm, self.to_hex())
+	}
+}
+
+impl fmt::Display for Digest {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if f.alternate() {
+			write!(f, "{}:{}", self.algorithm, self.to_hex())
+		} else {
+			write!(f, "{}", self.to_hex())
+		}
+	}
+}
+
+/// parses "<algorithm>:<hex>" (see [HashAlgorithm::from_str]) or a bare hex digest. For bare hex digests, the first
+/// algorithm of [HashAlgorithm::all] with a matching digest length will be used (e.g. 64 hex digits are parsed as
+/// sha256).
+impl FromStr for Digest {
+	type Err = PhollaitsError;
+
+	fn from_str(s: &str) -> Result<Digest> {
+		if let Some((algorithm, hex)) = s.trim().split_once(':') {
+			return Digest::from_hex(algorithm.parse()?, hex)
+		}
+		let hex = s.trim();
+		match HashAlgorithm::all().iter().find(|algorithm| algorithm.digest_len() * 2 == hex.len()) {
+			Some(algorithm) => Digest::from_hex(*algorithm, hex),
+			None => Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Invalid digest {}; no algorithm with a digest length of {} hex digits", s, hex.len())))
+		}
+	}
+}
+
+// compares the bytes in constant time (only the length may leak).
+pub(crate) fn constant_time_eq(first: &[u8], second: &[u8]) -> bool {
+	if first.len() != second.len() {
+		return false;
+	}
+	let difference = first.iter().zip(second).fold(0u8, |difference, (a, b)| difference | (a ^ b));
+	std::hint::black_box(difference) == 0
+}
+
+/// This trait implements several hash-algorithms for all readers (every type which implements [io::Read], e.g. files,
+/// [BufReader](std::io::BufReader)s, [tar::Entry]s or decompressors). For in-memory data and paths, see [HashDataExt].
 pub trait HashExt {
 	/// this method returns the md5-digest for implemented types as a [std::io::Result]
 	/// of [String].
 	/// # Example
 	/// ```
 	/// extern crate phollaits;
-	/// use phollaits::{HashExt, Result};
+	/// use phollaits::{HashDataExt, HashExt, Result};
 	/// use std::fs;
 	/// use std::io;
 	/// 
@@ -38,7 +367,7 @@ pub trait HashExt {
 	/// 	assert_eq!(io_read.md5sum()?, String::from(FILE01_HASH));
 	/// 
 	/// 	// usage with String
-	/// 	let mut s = fs::read_to_string(FILENAME_02).unwrap();
+	/// 	let s = fs::read_to_string(FILENAME_02).unwrap();
 	///		assert_eq!(s.md5sum()?, String::from(FILE02_HASH));
 	/// 
 	/// 	Ok(())
@@ -62,7 +391,7 @@ pub trait HashExt {
 	/// # Example
 	/// ```
 	/// extern crate phollaits;
-	/// use phollaits::{HashExt, Result};
+	/// use phollaits::{HashDataExt, HashExt, Result};
 	/// use std::fs;
 	/// use std::io;
 	/// 
@@ -76,7 +405,7 @@ pub trait HashExt {
 	/// 	assert_eq!(io_read.sha1sum()?, String::from(FILE01_HASH));
 	/// 
 	/// 	// usage with String
-	/// 	let mut s = fs::read_to_string(FILENAME_02).unwrap();
+	/// 	let s = fs::read_to_string(FILENAME_02).unwrap();
 	///		assert_eq!(s.sha1sum()?, String::from(FILE02_HASH));
 	/// 
 	/// 	Ok(())
@@ -100,7 +429,7 @@ pub trait HashExt {
 	/// # Example
 	/// ```
 	/// extern crate phollaits;
-	/// use phollaits::{HashExt, Result};
+	/// use phollaits::{HashDataExt, HashExt, Result};
 	/// use std::fs;
 	/// use std::io;
 	/// 
@@ -114,7 +443,7 @@ pub trait HashExt {
 	/// 	assert_eq!(io_read.sha256sum()?, String::from(FILE01_HASH));
 	/// 
 	/// 	// usage with String
-	/// 	let mut s = fs::read_to_string(FILENAME_02).unwrap();
+	/// 	let s = fs::read_to_string(FILENAME_02).unwrap();
 	///		assert_eq!(s.sha256sum()?, String::from(FILE02_HASH));
 	/// 
 	/// 	Ok(())
@@ -138,7 +467,7 @@ pub trait HashExt {
 	/// # Example
 	/// ```
 	/// extern crate phollaits;
-	/// use phollaits::{HashExt, Result};
+	/// use phollaits::{HashDataExt, HashExt, Result};
 	/// use std::fs;
 	/// use std::io;
 	/// 
@@ -152,7 +481,7 @@ pub trait HashExt {
 	/// 	assert_eq!(io_read.sha384sum()?, String::from(FILE01_HASH));
 	/// 
 	/// 	// usage with String
-	/// 	let mut s = fs::read_to_string(FILENAME_02).unwrap();
+	/// 	let s = fs::read_to_string(FILENAME_02).unwrap();
 	///		assert_eq!(s.sha384sum()?, String::from(FILE02_HASH));
 	/// 
 	/// 	Ok(())
@@ -176,7 +505,7 @@ pub trait HashExt {
 	/// # Example
 	/// ```
 	/// extern crate phollaits;
-	/// use phollaits::{HashExt, Result};
+	/// use phollaits::{HashDataExt, HashExt, Result};
 	/// use std::fs;
 	/// use std::io;
 	/// 
@@ -190,7 +519,7 @@ pub trait HashExt {
 	/// 	assert_eq!(io_read.sha512sum()?, String::from(FILE01_HASH));
 	/// 
 	/// 	// usage with String
-	/// 	let mut s = fs::read_to_string(FILENAME_02).unwrap();
+	/// 	let s = fs::read_to_string(FILENAME_02).unwrap();
 	///		assert_eq!(s.sha512sum()?, String::from(FILE02_HASH));
 	/// 
 	/// 	Ok(())
@@ -208,390 +537,706 @@ pub trait HashExt {
 	/// const FILE02_HASH: &'static str = "d9e6762dd1c8eaf6d61b3c6192fc408d4d6d5f1176d0c29169bc24e71c3f274ad27fcd5811b313d681f7e55ec02d73d499c95455b6b5bb503acf574fba8ffe85";
 	/// ```
 	fn sha512sum(&mut self) -> Result<String>;
+
+	/// this method returns the digests of all given algorithms, while reading the input only once. The digests will
+	/// be returned as lowercase hex [String]s.
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashAlgorithm, HashDataExt, HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	let digests = file.multi_hash(&[HashAlgorithm::Md5, HashAlgorithm::Sha1, HashAlgorithm::Sha256])?;
+	/// 	assert_eq!(digests[&HashAlgorithm::Md5], "a4494bd1b83303bc0872a996e6c8a8bf");
+	/// 	assert_eq!(digests[&HashAlgorithm::Sha1], "fc01e2d6df94d08cdf825ca0dc87a042ae73ba26");
+	/// 	assert_eq!(digests[&HashAlgorithm::Sha256], "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793");
+	///
+	/// 	let s = fs::read_to_string("assets/textfile.txt").unwrap();
+	/// 	let digests = s.multi_hash(&[HashAlgorithm::Md5])?;
+	/// 	assert_eq!(digests[&HashAlgorithm::Md5], "25f9e794323b453885f5181f1b624d0b");
+	/// 	Ok(())
+	/// }
+	/// ```
+	fn multi_hash(&mut self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, String>>;
+
+	/// this method returns the digest of the given algorithm (e.g. selected by a config file) as a lowercase hex
+	/// [String].
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashAlgorithm, HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let algorithm: HashAlgorithm = "md5".parse()?;
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	assert_eq!(file.hash_with(algorithm)?, "a4494bd1b83303bc0872a996e6c8a8bf");
+	/// 	Ok(())
+	/// }
+	/// ```
+	fn hash_with(&mut self, algorithm: HashAlgorithm) -> Result<String> {
+		self.hash_with_buffer_size(algorithm, DEFAULT_HASH_BUFFER_SIZE)
+	}
+
+	/// this method returns the sha3-224-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_224sum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_224)
+	}
+
+	/// this method returns the sha3-256-digest as a lowercase hex [String].
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashAlgorithm, HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	assert_eq!(file.sha3_256sum()?, "ea52ea28cd61eb2c70a28e455c0c41e0fd1119780aaf44612e0857f04aedeb8a");
+	///
+	/// 	// the other variants.
+	/// 	let algorithms = [HashAlgorithm::Sha3_224, HashAlgorithm::Sha3_384, HashAlgorithm::Sha3_512];
+	/// 	let digests = fs::File::open("assets/example.jpg").unwrap().multi_hash(&algorithms)?;
+	/// 	assert_eq!(digests[&HashAlgorithm::Sha3_224], FILE01_SHA3_224);
+	/// 	assert_eq!(digests[&HashAlgorithm::Sha3_384], FILE01_SHA3_384);
+	/// 	assert_eq!(digests[&HashAlgorithm::Sha3_512], FILE01_SHA3_512);
+	/// 	Ok(())
+	/// }
+	///
+	/// const FILE01_SHA3_224: &'static str = "154beaa876671444a5820f9f5e52a51f0a0377c0b7a93ef19f682935";
+	/// const FILE01_SHA3_384: &'static str = "ced08b5f4513b7589c89dcc31c177dc7e0f8caf5fcadc451e3f58c75e2185485517fd31ac572f1c0578304db6da03832";
+	/// const FILE01_SHA3_512: &'static str = "4f859df047c4ba047aa16877f9081008243e0c542c1289824f62b89690189c92c416ff0356e88be4c4f1ee52ef324545b378613408d008ee7025b46d866b982d";
+	/// ```
+	#[cfg(feature = "sha3")]
+	fn sha3_256sum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_256)
+	}
+
+	/// this method returns the sha3-384-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_384sum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_384)
+	}
+
+	/// this method returns the sha3-512-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_512sum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_512)
+	}
+
+	/// this method returns the BLAKE2b-512-digest as a lowercase hex [String].
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	assert_eq!(file.blake2bsum()?, String::from(FILE01_BLAKE2B));
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	assert_eq!(file.blake2ssum()?, String::from(FILE01_BLAKE2S));
+	/// 	Ok(())
+	/// }
+	///
+	/// const FILE01_BLAKE2B: &'static str = "443d6ec84440e3eea3d8b432c46a2f0d9a7216e9733dd19f8022d37e12dfa589d6a2d6bd4e8478476e6ffe6a56838184c6eac7baf95c501818b282d8d8a7d308";
+	/// const FILE01_BLAKE2S: &'static str = "ad995321804caadfc20985f89346ca1b680897a7816965c6a8184aea6304c959";
+	/// ```
+	#[cfg(feature = "blake2")]
+	fn blake2bsum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Blake2b)
+	}
+
+	/// this method returns the BLAKE2s-256-digest as a lowercase hex [String].
+	#[cfg(feature = "blake2")]
+	fn blake2ssum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Blake2s)
+	}
+
+	/// this method returns the BLAKE3-digest as a lowercase hex [String].
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	assert_eq!(file.blake3sum()?, "3d59547779e56665058c46eb95f0f35032300455a963541cee344363dc2325dc");
+	/// 	Ok(())
+	/// }
+	/// ```
+	#[cfg(feature = "blake3")]
+	fn blake3sum(&mut self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Blake3)
+	}
+
+	/// like [HashExt::hash_with], but reads the input with a buffer of the given size (in bytes).
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashAlgorithm, HashExt, Result};
+	/// use std::fs;
+	/// use std::io::BufReader;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut reader = BufReader::new(fs::File::open("assets/example.jpg").unwrap());
+	/// 	assert_eq!(reader.hash_with_buffer_size(HashAlgorithm::Md5, 1024 * 1024)?, "a4494bd1b83303bc0872a996e6c8a8bf");
+	/// 	Ok(())
+	/// }
+	/// ```
+	fn hash_with_buffer_size(&mut self, algorithm: HashAlgorithm, buffer_size: usize) -> Result<String>;
+
+	/// like [HashExt::multi_hash], but reads the input with a buffer of the given size (in bytes).
+	fn multi_hash_with_buffer_size(&mut self, algorithms: &[HashAlgorithm], buffer_size: usize) -> Result<BTreeMap<HashAlgorithm, String>>;
+
+	/// like [HashExt::hash_with], but returns a typed [Digest] instead of a hex [String].
+	fn digest_with(&mut self, algorithm: HashAlgorithm) -> Result<Digest> {
+		let mut digests = self.multi_digest(&[algorithm])?;
+		digests.remove(&algorithm).ok_or_else(|| missing_digest(algorithm))
+	}
+
+	/// like [HashExt::multi_hash], but returns typed [Digest]s instead of hex [String]s.
+	fn multi_digest(&mut self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		self.multi_digest_with_buffer_size(algorithms, DEFAULT_HASH_BUFFER_SIZE)
+	}
+
+	/// like [HashExt::multi_digest], but reads the input with a buffer of the given size (in bytes).
+	fn multi_digest_with_buffer_size(&mut self, algorithms: &[HashAlgorithm], buffer_size: usize) -> Result<BTreeMap<HashAlgorithm, Digest>>;
+
+	/// hashes the input in pieces of the given size (in bytes), e.g. for forensic images. The digests of all pieces and
+	/// the digest of the whole input will be calculated in one pass (see [PiecewiseHash] for the hash log).
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashAlgorithm, HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	let result = file.piecewise_hash(HashAlgorithm::Md5, 16 * 1024)?;
+	/// 	assert_eq!(result.total.to_hex(), "a4494bd1b83303bc0872a996e6c8a8bf");
+	/// 	assert_eq!(result.pieces.len() as u64, (result.length + 16 * 1024 - 1) / (16 * 1024));
+	/// 	assert_eq!(result.pieces[0].offset, 0);
+	/// 	assert_eq!(result.pieces[1].offset, 16 * 1024);
+	/// 	Ok(())
+	/// }
+	/// ```
+	fn piecewise_hash(&mut self, algorithm: HashAlgorithm, piece_size: u64) -> Result<PiecewiseHash>;
+
+	/// returns the [FuzzyHash] (ssdeep) of the input, which can be used to find similar inputs.
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::{HashDataExt, HashExt, Result};
+	/// use std::fs;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+	/// 	let hash = HashExt::fuzzy_hash(&mut file)?;
+	/// 	assert_eq!(hash, std::path::Path::new("assets/example.jpg").fuzzy_hash()?);
+	/// 	assert_eq!(hash.to_string(),
+	/// 		"1536:yHhPYy3Z0lXO2JdvNCE7GdInQuh6U1q1drJCwAuAdpPXtxb5Lb6GbIo:KhPYs0lewn7HfY1d1CwHgtxBb6QIo");
+	/// 	Ok(())
+	/// }
+	/// ```
+	fn fuzzy_hash(&mut self) -> Result<FuzzyHash>;
 }
 
-impl HashExt for dyn io::Read {
+impl<R: io::Read + ?Sized> HashExt for R {
 	fn md5sum(&mut self) -> Result<String> {
-		use md5::Digest;
-		let mut hasher = Md5::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		self.hash_with_buffer_size(HashAlgorithm::Md5, DEFAULT_HASH_BUFFER_SIZE)
 	}
 
 	fn sha1sum(&mut self) -> Result<String> {
-		use sha1::Digest;
-		let mut hasher = Sha1::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		self.hash_with_buffer_size(HashAlgorithm::Sha1, DEFAULT_HASH_BUFFER_SIZE)
 	}
 
 	fn sha256sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha256::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		self.hash_with_buffer_size(HashAlgorithm::Sha256, DEFAULT_HASH_BUFFER_SIZE)
 	}
 
 	fn sha384sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha384::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		self.hash_with_buffer_size(HashAlgorithm::Sha384, DEFAULT_HASH_BUFFER_SIZE)
 	}
 
 	fn sha512sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha512::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		self.hash_with_buffer_size(HashAlgorithm::Sha512, DEFAULT_HASH_BUFFER_SIZE)
 	}
-}
 
-impl HashExt for std::fs::File {
-	fn md5sum(&mut self) -> Result<String> {
-		use md5::Digest;
-		let mut hasher = Md5::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	fn multi_hash(&mut self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, String>> {
+		self.multi_hash_with_buffer_size(algorithms, DEFAULT_HASH_BUFFER_SIZE)
 	}
-	fn sha1sum(&mut self) -> Result<String> {
-		use sha1::Digest;
-		let mut hasher = Sha1::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+
+	fn hash_with_buffer_size(&mut self, algorithm: HashAlgorithm, buffer_size: usize) -> Result<String> {
+		let mut digests = self.multi_digest_with_buffer_size(&[algorithm], buffer_size)?;
+		digests.remove(&algorithm).map(|digest| digest.to_hex()).ok_or_else(|| missing_digest(algorithm))
 	}
-	fn sha256sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha256::new();
-		let mut buffer = [0; 1024];
+
+	fn multi_hash_with_buffer_size(&mut self, algorithms: &[HashAlgorithm], buffer_size: usize) -> Result<BTreeMap<HashAlgorithm, String>> {
+		Ok(to_hex_digests(self.multi_digest_with_buffer_size(algorithms, buffer_size)?))
+	}
+
+	fn multi_digest_with_buffer_size(&mut self, algorithms: &[HashAlgorithm], buffer_size: usize) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		let mut hashers = new_hashers(algorithms);
+		let mut buffer = vec![0; buffer_size.max(1)];
 		loop {
-			let count = match self.read(&mut buffer){
+			let count = match self.read(&mut buffer) {
 				Ok(x) => x,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
 				Err(e) => return Err(PhollaitsError::new(
 					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
+					format!("Error while trying to hash input; {}", e)))
 			};
 			if count == 0 {
 				break;
 			}
-			hasher.update(&buffer[..count]);
+			for hasher in hashers.values_mut() {
+				hasher.update(&buffer[..count]);
+			}
 		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		Ok(finalize_hashers(hashers))
 	}
-	fn sha384sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha384::new();
-		let mut buffer = [0; 1024];
+
+	fn piecewise_hash(&mut self, algorithm: HashAlgorithm, piece_size: u64) -> Result<PiecewiseHash> {
+		if piece_size == 0 {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				"Error while trying to hash input; the piece size must not be zero"));
+		}
+		let mut total = Hasher::new(algorithm);
+		let mut piece = Hasher::new(algorithm);
+		let mut pieces = Vec::new();
+		let mut length = 0u64;
+		let mut piece_length = 0u64;
+		let mut buffer = vec![0; DEFAULT_HASH_BUFFER_SIZE];
 		loop {
-			let count = match self.read(&mut buffer){
+			let count = match self.read(&mut buffer) {
 				Ok(x) => x,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
 				Err(e) => return Err(PhollaitsError::new(
 					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
+					format!("Error while trying to hash input; {}", e)))
 			};
 			if count == 0 {
 				break;
 			}
-			hasher.update(&buffer[..count]);
+			total.update(&buffer[..count]);
+			let mut data = &buffer[..count];
+			while !data.is_empty() {
+				// the data can span multiple pieces.
+				let take = (piece_size - piece_length).min(data.len() as u64) as usize;
+				piece.update(&data[..take]);
+				piece_length += take as u64;
+				data = &data[take..];
+				if piece_length == piece_size {
+					let finished = std::mem::replace(&mut piece, Hasher::new(algorithm));
+					pieces.push(HashPiece { offset: length, length: piece_length, digest: finished.finalize(algorithm) });
+					length += piece_length;
+					piece_length = 0;
+				}
+			}
+		}
+		if piece_length > 0 {
+			pieces.push(HashPiece { offset: length, length: piece_length, digest: piece.finalize(algorithm) });
+			length += piece_length;
 		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		Ok(PiecewiseHash {
+			algorithm,
+			piece_size,
+			length,
+			pieces,
+			total: total.finalize(algorithm),
+		})
 	}
-	fn sha512sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha512::new();
-		let mut buffer = [0; 1024];
+
+	fn fuzzy_hash(&mut self) -> Result<FuzzyHash> {
+		let mut hasher = FuzzyHasher::new();
+		let mut buffer = vec![0; DEFAULT_HASH_BUFFER_SIZE];
 		loop {
-			let count = match self.read(&mut buffer){
+			let count = match self.read(&mut buffer) {
 				Ok(x) => x,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
 				Err(e) => return Err(PhollaitsError::new(
 					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
+					format!("Error while trying to hash input; {}", e)))
 			};
 			if count == 0 {
 				break;
 			}
 			hasher.update(&buffer[..count]);
 		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		Ok(hasher.finalize())
 	}
 }
 
-impl<R: io::Read> HashExt for Entry<'_, R> {
-	fn md5sum(&mut self) -> Result<String> {
-		use md5::Digest;
-		let mut hasher = Md5::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+/// This trait implements the hash-algorithms of [HashExt] for in-memory data ([u8] slices, [Vec]s, [str], [String]s and
+/// [u64]s, which will be hashed as big-endian bytes) and for [Path]s (the file at the path will be hashed). In contrast
+/// to [HashExt], the methods do not require a mutable reference.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::{HashAlgorithm, HashDataExt, Result};
+/// use std::path::Path;
+///
+/// fn main() -> Result<()> {
+/// 	let data: &[u8] = b"phollaits";
+/// 	assert_eq!(data.md5sum()?, "phollaits".md5sum()?);
+/// 	assert_eq!(data.to_vec().md5sum()?, String::from("phollaits").md5sum()?);
+///
+/// 	let path = Path::new("assets/example.jpg");
+/// 	assert_eq!(path.sha256sum()?, "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793");
+/// 	assert_eq!(path.multi_hash(&[HashAlgorithm::Md5])?[&HashAlgorithm::Md5], "a4494bd1b83303bc0872a996e6c8a8bf");
+/// 	Ok(())
+/// }
+/// ```
+pub trait HashDataExt {
+	/// returns the [Digest]s of all given algorithms.
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>>;
+
+	/// returns the [FuzzyHash] (ssdeep) of the data (see [HashExt::fuzzy_hash]).
+	fn fuzzy_hash(&self) -> Result<FuzzyHash>;
+
+	/// returns the [Digest] of the given algorithm.
+	fn digest_with(&self, algorithm: HashAlgorithm) -> Result<Digest> {
+		let mut digests = self.multi_digest(&[algorithm])?;
+		digests.remove(&algorithm).ok_or_else(|| missing_digest(algorithm))
 	}
 
-	fn sha1sum(&mut self) -> Result<String> {
-		use sha1::Digest;
-		let mut hasher = Sha1::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	/// returns the digests of all given algorithms as lowercase hex [String]s.
+	fn multi_hash(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, String>> {
+		Ok(to_hex_digests(self.multi_digest(algorithms)?))
 	}
 
-	fn sha256sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha256::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	/// returns the digest of the given algorithm as a lowercase hex [String].
+	fn hash_with(&self, algorithm: HashAlgorithm) -> Result<String> {
+		Ok(self.digest_with(algorithm)?.to_hex())
 	}
 
-	fn sha384sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha384::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	/// returns the md5-digest as a lowercase hex [String].
+	fn md5sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Md5)
 	}
 
-	fn sha512sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha512::new();
-		let mut buffer = [0; 1024];
-		loop {
-			let count = match self.read(&mut buffer){
-				Ok(x) => x,
-				Err(e) => return Err(PhollaitsError::new(
-					PhollaitsErrorKind::HashingError,
-					format!("Error while trying to hash input; {}", e.to_string())))
-			};
-			if count == 0 {
-				break;
-			}
-			hasher.update(&buffer[..count]);
-		}
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	/// returns the sha1-digest as a lowercase hex [String].
+	fn sha1sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha1)
+	}
+
+	/// returns the sha256-digest as a lowercase hex [String].
+	fn sha256sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha256)
+	}
+
+	/// returns the sha384-digest as a lowercase hex [String].
+	fn sha384sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha384)
+	}
+
+	/// returns the sha512-digest as a lowercase hex [String].
+	fn sha512sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha512)
+	}
+
+	/// returns the sha3-224-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_224sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_224)
+	}
+
+	/// returns the sha3-256-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_256sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_256)
+	}
+
+	/// returns the sha3-384-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_384sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_384)
+	}
+
+	/// returns the sha3-512-digest as a lowercase hex [String].
+	#[cfg(feature = "sha3")]
+	fn sha3_512sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Sha3_512)
+	}
+
+	/// returns the BLAKE2b-512-digest as a lowercase hex [String].
+	#[cfg(feature = "blake2")]
+	fn blake2bsum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Blake2b)
+	}
+
+	/// returns the BLAKE2s-256-digest as a lowercase hex [String].
+	#[cfg(feature = "blake2")]
+	fn blake2ssum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Blake2s)
+	}
+
+	/// returns the BLAKE3-digest as a lowercase hex [String].
+	#[cfg(feature = "blake3")]
+	fn blake3sum(&self) -> Result<String> {
+		self.hash_with(HashAlgorithm::Blake3)
 	}
 }
 
-impl HashExt for String {
-	fn md5sum(&mut self) -> Result<String> {
-		use md5::Digest;
-		let mut hasher = Md5::new();
-		hasher.update(self);
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+impl HashDataExt for [u8] {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		let mut hashers = new_hashers(algorithms);
+		for hasher in hashers.values_mut() {
+			hasher.update(self);
+		}
+		Ok(finalize_hashers(hashers))
 	}
 
-	fn sha1sum(&mut self) -> Result<String> {
-		use sha1::Digest;
-		let mut hasher = Sha1::new();
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		let mut hasher = FuzzyHasher::new();
 		hasher.update(self);
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+		Ok(hasher.finalize())
 	}
+}
 
-	fn sha256sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha256::new();
-		hasher.update(self);
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+impl HashDataExt for Vec<u8> {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		self[..].multi_digest(algorithms)
 	}
 
-	fn sha384sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha384::new();
-		hasher.update(self);
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		self[..].fuzzy_hash()
 	}
+}
 
-	fn sha512sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha512::new();
-		hasher.update(self);
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+impl HashDataExt for str {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		self.as_bytes().multi_digest(algorithms)
+	}
+
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		self.as_bytes().fuzzy_hash()
 	}
 }
 
-impl HashExt for u64 {
-	fn md5sum(&mut self) -> Result<String> {
-		use md5::Digest;
-		let mut hasher = Md5::new();
-		hasher.update(self.to_be_bytes());
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+impl HashDataExt for String {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		self.as_bytes().multi_digest(algorithms)
 	}
 
-	fn sha1sum(&mut self) -> Result<String> {
-		use sha1::Digest;
-		let mut hasher = Sha1::new();
-		hasher.update(self.to_be_bytes());
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		self.as_bytes().fuzzy_hash()
 	}
+}
 
-	fn sha256sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha256::new();
-		hasher.update(self.to_be_bytes());
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+impl HashDataExt for u64 {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		self.to_be_bytes()[..].multi_digest(algorithms)
 	}
 
-	fn sha384sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha384::new();
-		hasher.update(self.to_be_bytes());
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		self.to_be_bytes()[..].fuzzy_hash()
+	}
+}
+
+impl HashDataExt for Path {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		let mut file = match File::open(self) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to open {}; {}", self.display(), e)))
+		};
+		HashExt::multi_digest(&mut file, algorithms)
 	}
 
-	fn sha512sum(&mut self) -> Result<String> {
-		use sha2::Digest;
-		let mut hasher = Sha512::new();
-		hasher.update(self.to_be_bytes());
-		let hasher = hasher.finalize();
-		Ok(HEXLOWER.encode(hasher.as_ref()))
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		let mut file = match File::open(self) {
+			Ok(x) => x,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to open {}; {}", self.display(), e)))
+		};
+		HashExt::fuzzy_hash(&mut file)
+	}
+}
+
+impl HashDataExt for PathBuf {
+	fn multi_digest(&self, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+		self.as_path().multi_digest(algorithms)
+	}
+
+	fn fuzzy_hash(&self) -> Result<FuzzyHash> {
+		self.as_path().fuzzy_hash()
+	}
+}
+
+/// hashes the regular file at the path with all given algorithms, using a memory map instead of reading the file
+/// with a buffer (only available with the feature "mmap"). The file must not be modified while it is hashed.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let digests = mmap_multi_digest("assets/example.jpg", &[HashAlgorithm::Md5, HashAlgorithm::Sha256])?;
+/// 	assert_eq!(digests[&HashAlgorithm::Md5].to_hex(), "a4494bd1b83303bc0872a996e6c8a8bf");
+/// 	assert_eq!(mmap_digest("assets/example.jpg", HashAlgorithm::Sha256)?, digests[&HashAlgorithm::Sha256]);
+/// 	assert!(mmap_digest("assets", HashAlgorithm::Sha256).is_err());
+/// 	Ok(())
+/// }
+/// ```
+#[cfg(feature = "mmap")]
+pub fn mmap_multi_digest<P: AsRef<Path>>(path: P, algorithms: &[HashAlgorithm]) -> Result<BTreeMap<HashAlgorithm, Digest>> {
+	let path = path.as_ref();
+	let mmap_error = |e: io::Error| PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to map {}; {}", path.display(), e));
+	let file = File::open(path).map_err(mmap_error)?;
+	let metadata = file.metadata().map_err(mmap_error)?;
+	if !metadata.is_file() {
+		return Err(mmap_error(io::Error::new(io::ErrorKind::InvalidInput, "not a regular file")));
+	}
+	// empty files can not be mapped.
+	if metadata.len() == 0 {
+		return HashDataExt::multi_digest(&[][..], algorithms);
 	}
-}
\ No newline at end of file
+	// safety: the map is only read and the caller must ensure, that the file is not modified while it is hashed.
+	let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(mmap_error)?;
+	#[cfg(unix)]
+	let _ = mmap.advise(memmap2::Advice::Sequential);
+	HashDataExt::multi_digest(&mmap[..], algorithms)
+}
+
+/// like [mmap_multi_digest], but with a single algorithm.
+#[cfg(feature = "mmap")]
+pub fn mmap_digest<P: AsRef<Path>>(path: P, algorithm: HashAlgorithm) -> Result<Digest> {
+	let mut digests = mmap_multi_digest(path, &[algorithm])?;
+	digests.remove(&algorithm).ok_or_else(|| missing_digest(algorithm))
+}
+
+// a hasher for one of the [HashAlgorithm]s.
+pub(crate) enum Hasher {
+	Md5(Md5),
+	Sha1(Sha1),
+	Sha256(Sha256),
+	Sha384(Sha384),
+	Sha512(Sha512),
+	#[cfg(feature = "sha3")]
+	Sha3_224(Sha3_224),
+	#[cfg(feature = "sha3")]
+	Sha3_256(Sha3_256),
+	#[cfg(feature = "sha3")]
+	Sha3_384(Sha3_384),
+	#[cfg(feature = "sha3")]
+	Sha3_512(Sha3_512),
+	#[cfg(feature = "blake2")]
+	Blake2b(Blake2b),
+	#[cfg(feature = "blake2")]
+	Blake2s(Blake2s),
+	#[cfg(feature = "blake3")]
+	Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+	pub(crate) fn new(algorithm: HashAlgorithm) -> Hasher {
+		use sha2::Digest as _;
+		match algorithm {
+			HashAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+			HashAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+			HashAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+			HashAlgorithm::Sha384 => Hasher::Sha384(Sha384::new()),
+			HashAlgorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_224 => Hasher::Sha3_224(Sha3_224::new()),
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_256 => Hasher::Sha3_256(Sha3_256::new()),
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_384 => Hasher::Sha3_384(Sha3_384::new()),
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_512 => Hasher::Sha3_512(Sha3_512::new()),
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2b => Hasher::Blake2b(Blake2b::new()),
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2s => Hasher::Blake2s(Blake2s::new()),
+			#[cfg(feature = "blake3")]
+			HashAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+		}
+	}
+
+	pub(crate) fn update(&mut self, data: &[u8]) {
+		use sha2::Digest as _;
+		match self {
+			Hasher::Md5(x) => x.update(data),
+			Hasher::Sha1(x) => x.update(data),
+			Hasher::Sha256(x) => x.update(data),
+			Hasher::Sha384(x) => x.update(data),
+			Hasher::Sha512(x) => x.update(data),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_224(x) => x.update(data),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_256(x) => x.update(data),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_384(x) => x.update(data),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_512(x) => x.update(data),
+			#[cfg(feature = "blake2")]
+			Hasher::Blake2b(x) => x.update(data),
+			#[cfg(feature = "blake2")]
+			Hasher::Blake2s(x) => x.update(data),
+			#[cfg(feature = "blake3")]
+			Hasher::Blake3(x) => {
+				x.update(data);
+			},
+		}
+	}
+
+	pub(crate) fn finalize(self, algorithm: HashAlgorithm) -> Digest {
+		use sha2::Digest as _;
+		let bytes = match self {
+			Hasher::Md5(x) => x.finalize().to_vec(),
+			Hasher::Sha1(x) => x.finalize().to_vec(),
+			Hasher::Sha256(x) => x.finalize().to_vec(),
+			Hasher::Sha384(x) => x.finalize().to_vec(),
+			Hasher::Sha512(x) => x.finalize().to_vec(),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_224(x) => x.finalize().to_vec(),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_256(x) => x.finalize().to_vec(),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_384(x) => x.finalize().to_vec(),
+			#[cfg(feature = "sha3")]
+			Hasher::Sha3_512(x) => x.finalize().to_vec(),
+			#[cfg(feature = "blake2")]
+			Hasher::Blake2b(x) => x.finalize().to_vec(),
+			#[cfg(feature = "blake2")]
+			Hasher::Blake2s(x) => x.finalize().to_vec(),
+			#[cfg(feature = "blake3")]
+			Hasher::Blake3(x) => x.finalize().as_bytes().to_vec(),
+		};
+		Digest::from_parts(algorithm, bytes)
+	}
+}
+
+// lowercase, without "-" and "_".
+fn normalize_algorithm_name(name: &str) -> String {
+	name.trim().chars().filter(|c| *c != '-' && *c != '_').collect::<String>().to_lowercase()
+}
+
+// creates one hasher per (distinct) algorithm.
+pub(crate) fn new_hashers(algorithms: &[HashAlgorithm]) -> BTreeMap<HashAlgorithm, Hasher> {
+	algorithms.iter().map(|algorithm| (*algorithm, Hasher::new(*algorithm))).collect()
+}
+
+pub(crate) fn finalize_hashers(hashers: BTreeMap<HashAlgorithm, Hasher>) -> BTreeMap<HashAlgorithm, Digest> {
+	hashers.into_iter().map(|(algorithm, hasher)| (algorithm, hasher.finalize(algorithm))).collect()
+}
+
+fn to_hex_digests(digests: BTreeMap<HashAlgorithm, Digest>) -> BTreeMap<HashAlgorithm, String> {
+	digests.into_iter().map(|(algorithm, digest)| (algorithm, digest.to_hex())).collect()
+}
+
+fn missing_digest(algorithm: HashAlgorithm) -> PhollaitsError {
+	PhollaitsError::new(PhollaitsErrorKind::HashingError, format!("Error while trying to hash input; missing {} digest", algorithm))
+}
@@ -0,0 +1,160 @@
+/*************************************************************************
+* ph0llux:b4f1a8e03c7d29e56b0a4f81d3c92e7a05b6d1f48e3c07a92b5d6f1e0a4c8d37
+*************************************************************************/
+//!hashio Module (readers and writers, which hash all bytes passing through).
+
+// - STD
+use std::collections::BTreeMap;
+use std::io;
+
+// - internal
+use super::{finalize_hashers, new_hashers, Digest, HashAlgorithm, Hasher};
+
+/// A reader, which forwards all bytes of the inner reader and hashes them with the given algorithms. This avoids
+/// reading the data a second time (e.g. with [HashExt](crate::HashExt)) after it was copied.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+/// use std::fs;
+/// use std::io;
+///
+/// fn main() -> io::Result<()> {
+/// 	let file = fs::File::open("assets/example.jpg")?;
+/// 	let mut reader = HashingReader::new(file, &[HashAlgorithm::Md5, HashAlgorithm::Sha256]);
+/// 	let mut copy = Vec::new();
+/// 	io::copy(&mut reader, &mut copy)?;
+/// 	assert_eq!(reader.byte_count(), copy.len() as u64);
+/// 	let digests = reader.finalize();
+/// 	assert_eq!(digests[&HashAlgorithm::Md5].to_hex(), "a4494bd1b83303bc0872a996e6c8a8bf");
+/// 	Ok(())
+/// }
+/// ```
+pub struct HashingReader<R> {
+	inner: R,
+	hashers: BTreeMap<HashAlgorithm, Hasher>,
+	byte_count: u64,
+}
+
+impl<R> HashingReader<R> {
+	/// returns a new reader, which hashes all read bytes with the given algorithms.
+	pub fn new(inner: R, algorithms: &[HashAlgorithm]) -> HashingReader<R> {
+		HashingReader {
+			inner,
+			hashers: new_hashers(algorithms),
+			byte_count: 0,
+		}
+	}
+
+	/// returns the number of bytes, which were read (and hashed) so far.
+	pub fn byte_count(&self) -> u64 {
+		self.byte_count
+	}
+
+	/// returns a reference to the inner reader.
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	/// returns a mutable reference to the inner reader. Bytes read directly from the inner reader will not be hashed.
+	pub fn get_mut(&mut self) -> &mut R {
+		&mut self.inner
+	}
+
+	/// returns the digests of all bytes read so far.
+	pub fn finalize(self) -> BTreeMap<HashAlgorithm, Digest> {
+		self.into_parts().1
+	}
+
+	/// returns the inner reader and the digests of all bytes read so far.
+	pub fn into_parts(self) -> (R, BTreeMap<HashAlgorithm, Digest>) {
+		(self.inner, finalize_hashers(self.hashers))
+	}
+}
+
+impl<R: io::Read> io::Read for HashingReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		let count = self.inner.read(buf)?;
+		for hasher in self.hashers.values_mut() {
+			hasher.update(&buf[..count]);
+		}
+		self.byte_count += count as u64;
+		Ok(count)
+	}
+}
+
+/// A writer, which forwards all bytes to the inner writer and hashes the bytes accepted by the inner writer with the
+/// given algorithms.
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate phollaits;
+/// use phollaits::*;
+/// use tar::Builder;
+///
+/// fn main() -> Result<()> {
+/// 	let writer = HashingWriter::new(Vec::new(), &[HashAlgorithm::Sha256]);
+/// 	let mut builder = Builder::new(writer);
+/// 	builder.append_file_directly("assets/example.jpg")?;
+/// 	let (archive, digests) = builder.into_inner().unwrap().into_parts();
+/// 	assert_eq!(digests[&HashAlgorithm::Sha256], archive.digest_with(HashAlgorithm::Sha256)?);
+/// 	Ok(())
+/// }
+/// ```
+pub struct HashingWriter<W> {
+	inner: W,
+	hashers: BTreeMap<HashAlgorithm, Hasher>,
+	byte_count: u64,
+}
+
+impl<W> HashingWriter<W> {
+	/// returns a new writer, which hashes all written bytes with the given algorithms.
+	pub fn new(inner: W, algorithms: &[HashAlgorithm]) -> HashingWriter<W> {
+		HashingWriter {
+			inner,
+			hashers: new_hashers(algorithms),
+			byte_count: 0,
+		}
+	}
+
+	/// returns the number of bytes, which were written (and hashed) so far.
+	pub fn byte_count(&self) -> u64 {
+		self.byte_count
+	}
+
+	/// returns a reference to the inner writer.
+	pub fn get_ref(&self) -> &W {
+		&self.inner
+	}
+
+	/// returns a mutable reference to the inner writer. Bytes written directly to the inner writer will not be hashed.
+	pub fn get_mut(&mut self) -> &mut W {
+		&mut self.inner
+	}
+
+	/// returns the digests of all bytes written so far. The inner writer will not be flushed.
+	pub fn finalize(self) -> BTreeMap<HashAlgorithm, Digest> {
+		self.into_parts().1
+	}
+
+	/// returns the inner writer and the digests of all bytes written so far.
+	pub fn into_parts(self) -> (W, BTreeMap<HashAlgorithm, Digest>) {
+		(self.inner, finalize_hashers(self.hashers))
+	}
+}
+
+impl<W: io::Write> io::Write for HashingWriter<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		let count = self.inner.write(buf)?;
+		// only the bytes accepted by the inner writer.
+		for hasher in self.hashers.values_mut() {
+			hasher.update(&buf[..count]);
+		}
+		self.byte_count += count as u64;
+		Ok(count)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.inner.flush()
+	}
+}
@@ -0,0 +1,206 @@
+/*************************************************************************
+* ph0llux:2c7e9a14d05b83f6e1a9c4d27b08f53e6a1d94c07b2e58f13a6c90d4e7b21f85
+*************************************************************************/
+//!mac Module (HMAC for all algorithms of [HashAlgorithm] and the keyed mode of BLAKE3).
+
+// - STD
+use std::io;
+
+// - internal
+use super::{constant_time_eq, Digest, HashAlgorithm, Hasher, PhollaitsError, PhollaitsErrorKind, Result, DEFAULT_HASH_BUFFER_SIZE};
+
+const HMAC_INNER_PAD: u8 = 0x36;
+const HMAC_OUTER_PAD: u8 = 0x5c;
+
+/// A streaming message authentication code: HMAC (RFC 2104) with any [HashAlgorithm], or the keyed mode of BLAKE3
+/// (with the feature "blake3"). The input can be fed in chunks with [Mac::update]. For readers, see [HmacExt].
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::{HashAlgorithm, Mac};
+///
+/// fn main() {
+/// 	let mut mac = Mac::new(HashAlgorithm::Sha256, b"key");
+/// 	mac.update(b"The quick brown fox ");
+/// 	mac.update(b"jumps over the lazy dog");
+/// 	assert_eq!(mac.finalize().to_hex(), "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+///
+/// 	let mut mac = Mac::new(HashAlgorithm::Sha256, b"key");
+/// 	mac.update(b"The quick brown fox jumps over the lazy dog");
+/// 	let tag = phollaits::Digest::from_hex(HashAlgorithm::Sha256, "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8").unwrap();
+/// 	assert!(mac.verify(tag.as_bytes()));
+/// }
+/// ```
+pub struct Mac {
+	state: MacState,
+}
+
+enum MacState {
+	Hmac {
+		algorithm: HashAlgorithm,
+		inner: Box<Hasher>,
+		// the key, xored with the outer pad.
+		outer_key: Vec<u8>,
+	},
+	#[cfg(feature = "blake3")]
+	Blake3Keyed(Box<blake3::Hasher>),
+}
+
+impl Mac {
+	/// returns a new HMAC with the given algorithm and key (of any length).
+	pub fn new(algorithm: HashAlgorithm, key: &[u8]) -> Mac {
+		let block_len = algorithm.block_len();
+		// keys longer than the block length will be hashed, shorter keys will be padded with zeros.
+		let mut block_key = if key.len() > block_len {
+			let mut hasher = Hasher::new(algorithm);
+			hasher.update(key);
+			hasher.finalize(algorithm).into_bytes()
+		} else {
+			key.to_vec()
+		};
+		block_key.resize(block_len, 0);
+
+		let inner_key: Vec<u8> = block_key.iter().map(|byte| byte ^ HMAC_INNER_PAD).collect();
+		let outer_key = block_key.iter().map(|byte| byte ^ HMAC_OUTER_PAD).collect();
+		let mut inner = Box::new(Hasher::new(algorithm));
+		inner.update(&inner_key);
+		Mac {
+			state: MacState::Hmac { algorithm, inner, outer_key },
+		}
+	}
+
+	/// returns a new MAC, using the keyed mode of BLAKE3 with the given 256 bit key.
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::Mac;
+	///
+	/// fn main() {
+	/// 	let mut mac = Mac::new_blake3_keyed(&[0x42; 32]);
+	/// 	mac.update(b"phollaits");
+	/// 	assert_eq!(mac.finalize().to_hex(), "e417bc594059fe4f34291179a847a41220fcab9a9e0d16772b506080185955cd");
+	/// }
+	/// ```
+	#[cfg(feature = "blake3")]
+	pub fn new_blake3_keyed(key: &[u8; 32]) -> Mac {
+		Mac {
+			state: MacState::Blake3Keyed(Box::new(blake3::Hasher::new_keyed(key))),
+		}
+	}
+
+	/// returns the underlying algorithm.
+	pub fn algorithm(&self) -> HashAlgorithm {
+		match &self.state {
+			MacState::Hmac { algorithm, .. } => *algorithm,
+			#[cfg(feature = "blake3")]
+			MacState::Blake3Keyed(_) => HashAlgorithm::Blake3,
+		}
+	}
+
+	/// feeds the data into the MAC.
+	pub fn update(&mut self, data: &[u8]) {
+		match &mut self.state {
+			MacState::Hmac { inner, .. } => inner.update(data),
+			#[cfg(feature = "blake3")]
+			MacState::Blake3Keyed(hasher) => {
+				hasher.update(data);
+			},
+		}
+	}
+
+	/// returns the tag as a [Digest] of the underlying algorithm.
+	pub fn finalize(self) -> Digest {
+		match self.state {
+			MacState::Hmac { algorithm, inner, outer_key } => {
+				let inner_digest = inner.finalize(algorithm);
+				let mut outer = Hasher::new(algorithm);
+				outer.update(&outer_key);
+				outer.update(inner_digest.as_bytes());
+				outer.finalize(algorithm)
+			},
+			#[cfg(feature = "blake3")]
+			MacState::Blake3Keyed(hasher) => Digest::from_parts(HashAlgorithm::Blake3, hasher.finalize().as_bytes().to_vec()),
+		}
+	}
+
+	/// compares the tag with the expected tag in constant time.
+	pub fn verify(self, expected_tag: &[u8]) -> bool {
+		constant_time_eq(self.finalize().as_bytes(), expected_tag)
+	}
+}
+
+/// This trait calculates message authentication codes (see [Mac]) for all readers. The input will be read in chunks,
+/// like by [HashExt](crate::HashExt).
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::{HashAlgorithm, HmacExt, Result};
+/// use std::fs;
+///
+/// fn main() -> Result<()> {
+/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+/// 	let tag = file.hmac(HashAlgorithm::Sha256, b"key")?;
+/// 	assert_eq!(tag.to_hex(), "e3263562abd75201c454201eb0d616e4d2a7e7dc930b971cde9d9d02fd812080");
+///
+/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+/// 	assert!(file.verify_hmac(HashAlgorithm::Sha256, b"key", tag.as_bytes())?);
+/// 	let mut file = fs::File::open("assets/example.jpg").unwrap();
+/// 	assert!(!file.verify_hmac(HashAlgorithm::Sha256, b"wrong key", tag.as_bytes())?);
+/// 	Ok(())
+/// }
+/// ```
+pub trait HmacExt {
+	/// returns the HMAC of the input with the given algorithm and key.
+	fn hmac(&mut self, algorithm: HashAlgorithm, key: &[u8]) -> Result<Digest>;
+
+	/// calculates the HMAC of the input and compares it with the expected tag in constant time.
+	fn verify_hmac(&mut self, algorithm: HashAlgorithm, key: &[u8], expected_tag: &[u8]) -> Result<bool>;
+
+	/// returns the keyed BLAKE3 hash of the input (see [Mac::new_blake3_keyed]).
+	#[cfg(feature = "blake3")]
+	fn blake3_keyed(&mut self, key: &[u8; 32]) -> Result<Digest>;
+
+	/// calculates the keyed BLAKE3 hash of the input and compares it with the expected tag in constant time.
+	#[cfg(feature = "blake3")]
+	fn verify_blake3_keyed(&mut self, key: &[u8; 32], expected_tag: &[u8]) -> Result<bool>;
+}
+
+impl<R: io::Read + ?Sized> HmacExt for R {
+	fn hmac(&mut self, algorithm: HashAlgorithm, key: &[u8]) -> Result<Digest> {
+		mac_reader(self, Mac::new(algorithm, key))
+	}
+
+	fn verify_hmac(&mut self, algorithm: HashAlgorithm, key: &[u8], expected_tag: &[u8]) -> Result<bool> {
+		let tag = self.hmac(algorithm, key)?;
+		Ok(constant_time_eq(tag.as_bytes(), expected_tag))
+	}
+
+	#[cfg(feature = "blake3")]
+	fn blake3_keyed(&mut self, key: &[u8; 32]) -> Result<Digest> {
+		mac_reader(self, Mac::new_blake3_keyed(key))
+	}
+
+	#[cfg(feature = "blake3")]
+	fn verify_blake3_keyed(&mut self, key: &[u8; 32], expected_tag: &[u8]) -> Result<bool> {
+		let tag = self.blake3_keyed(key)?;
+		Ok(constant_time_eq(tag.as_bytes(), expected_tag))
+	}
+}
+
+fn mac_reader<R: io::Read + ?Sized>(reader: &mut R, mut mac: Mac) -> Result<Digest> {
+	let mut buffer = vec![0; DEFAULT_HASH_BUFFER_SIZE];
+	loop {
+		let count = match reader.read(&mut buffer) {
+			Ok(x) => x,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to authenticate input; {}", e)))
+		};
+		if count == 0 {
+			break;
+		}
+		mac.update(&buffer[..count]);
+	}
+	Ok(mac.finalize())
+}
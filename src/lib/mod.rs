@@ -1,6 +1,12 @@
 /*************************************************************************
 * ph0llux:5bade9a3fa0817dfbba6da19abaaa598628eff26f6f8616015c5ad79ae9c50de
 *************************************************************************/
+//! Some useful Rust traits, which I like to use in my projects.
+//! # Migrating from 0.3
+//! Since 0.4, [HashExt] is implemented for every type which implements [io::Read] (and therefore takes `&mut self`).
+//! In-memory data ([String], [str], [u64], [u8] slices and [Vec]s) and [Path](std::path::Path)s are hashed by the new
+//! trait [HashDataExt], so code like `String::from("phollaits").md5sum()` only needs to import [HashDataExt] instead
+//! of [HashExt].
 // 
 // - STD
 use std::io;
@@ -16,20 +22,54 @@ extern crate base64;
 // 
 // - internal
 pub use archive::*;
+pub use batch::*;
+#[cfg(feature = "tokio")]
+pub use async_archive::*;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub use compression::*;
+pub use checksum::*;
+pub use chunking::*;
+pub use cpio::*;
+pub use fuzzy::*;
 pub use hash::*;
+pub use hashio::*;
+pub use mac::*;
+pub use piecewise::*;
+pub use resumable::*;
+pub use search::*;
 pub use stdext::*;
+pub use treehash::*;
 pub use converter::*;
 pub use encoder::*;
 pub use errors::*;
+pub use evidence::*;
+pub use extract::*;
 
 // 
 // - modules
 mod archive;
+mod batch;
+#[cfg(feature = "tokio")]
+mod async_archive;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+mod compression;
+mod checksum;
+mod chunking;
+mod cpio;
+mod fuzzy;
 mod hash;
+mod hashio;
+mod mac;
+mod piecewise;
+mod resumable;
+mod search;
 mod stdext;
+mod treehash;
 mod converter;
 mod encoder;
 mod errors;
+mod evidence;
+mod extract;
 
 pub type Result<T> = std::result::Result<T, PhollaitsError>;
 
@@ -0,0 +1,142 @@
+/*************************************************************************
+* ph0llux:4e09b7d2c61a38f5e0b29d7c4a15f83e2d6c07b91a4f58e3c2d07a96b1e54f3c
+*************************************************************************/
+//!piecewise Module (piecewise hashing and hash logs for forensic images, see [HashExt::piecewise_hash]).
+
+// - STD
+use std::io;
+
+// - internal
+use super::{Digest, HashAlgorithm, PhollaitsError, PhollaitsErrorKind, Result};
+#[cfg(doc)]
+use super::HashExt;
+
+/// The digest of a single piece of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashPiece {
+	/// the offset of the piece in the input.
+	pub offset: u64,
+	/// the length of the piece (only the last piece can be shorter than the piece size).
+	pub length: u64,
+	pub digest: Digest,
+}
+
+/// The result of [HashExt::piecewise_hash].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiecewiseHash {
+	pub algorithm: HashAlgorithm,
+	pub piece_size: u64,
+	/// the total length of the input.
+	pub length: u64,
+	pub pieces: Vec<HashPiece>,
+	/// the digest of the whole input.
+	pub total: Digest,
+}
+
+impl PiecewiseHash {
+	/// writes the hash log, in the style of dc3dd. The ranges of the pieces are inclusive:
+	/// ```text
+	/// algorithm: sha256
+	/// piece size: 1048576
+	/// length: 1500000
+	/// 0-1048575: <hex digest of the first piece>
+	/// 1048576-1499999: <hex digest of the second piece>
+	/// total: <hex digest of the whole input>
+	/// ```
+	pub fn write_log<W: io::Write>(&self, mut writer: W) -> Result<()> {
+		let mut log = format!("algorithm: {}\npiece size: {}\nlength: {}\n", self.algorithm, self.piece_size, self.length);
+		for piece in &self.pieces {
+			log.push_str(&format!("{}-{}: {}\n", piece.offset, piece.offset + piece.length - 1, piece.digest));
+		}
+		log.push_str(&format!("total: {}\n", self.total));
+		match writer.write_all(log.as_bytes()) {
+			Ok(_) => Ok(()),
+			Err(e) => Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				format!("Error while trying to write hash log; {}", e)))
+		}
+	}
+
+	/// parses a hash log, written by [PiecewiseHash::write_log].
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::*;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut image = vec![0u8; 10000];
+	/// 	let original = (&image[..]).piecewise_hash(HashAlgorithm::Sha256, 4096)?;
+	/// 	let mut log = Vec::new();
+	/// 	original.write_log(&mut log)?;
+	/// 	let original = PiecewiseHash::parse_log(&String::from_utf8(log).unwrap())?;
+	///
+	/// 	// a damaged byte in the second piece.
+	/// 	image[5000] = 0xff;
+	/// 	let damaged = (&image[..]).piecewise_hash(HashAlgorithm::Sha256, 4096)?;
+	/// 	let differing = original.differing_pieces(&damaged)?;
+	/// 	assert_eq!(differing.len(), 1);
+	/// 	assert_eq!((differing[0].offset, differing[0].length), (4096, 4096));
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn parse_log(log: &str) -> Result<PiecewiseHash> {
+		let mut lines = log.lines().map(|line| line.trim_end_matches('\r')).filter(|line| !line.trim().is_empty());
+		let algorithm: HashAlgorithm = log_value(lines.next(), "algorithm")?.parse()?;
+		let piece_size = log_value(lines.next(), "piece size")?.parse()?;
+		let length = log_value(lines.next(), "length")?.parse()?;
+		let mut pieces = Vec::new();
+		let mut total = None;
+		for line in lines {
+			let (key, hex) = match line.split_once(": ") {
+				Some(x) => x,
+				None => return Err(invalid_log(line)),
+			};
+			let digest = Digest::from_hex(algorithm, hex.trim())?;
+			if key == "total" {
+				total = Some(digest);
+				break;
+			}
+			let (start, end) = match key.split_once('-') {
+				Some(x) => x,
+				None => return Err(invalid_log(line)),
+			};
+			let offset: u64 = start.trim().parse()?;
+			let end: u64 = end.trim().parse()?;
+			if end < offset {
+				return Err(invalid_log(line));
+			}
+			pieces.push(HashPiece { offset, length: end - offset + 1, digest });
+		}
+		match total {
+			Some(total) => Ok(PiecewiseHash { algorithm, piece_size, length, pieces, total }),
+			None => Err(invalid_log("missing total digest")),
+		}
+	}
+
+	/// returns the pieces, whose digests differ from (or which are missing in) the other result. Both results must use
+	/// the same algorithm and piece size.
+	pub fn differing_pieces(&self, other: &PiecewiseHash) -> Result<Vec<HashPiece>> {
+		if self.algorithm != other.algorithm || self.piece_size != other.piece_size {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::HashingError,
+				"Error while trying to compare piecewise hashes; different algorithms or piece sizes"));
+		}
+		Ok(self.pieces.iter().enumerate()
+			.filter(|(index, piece)| other.pieces.get(*index) != Some(piece))
+			.map(|(_, piece)| piece.clone())
+			.collect())
+	}
+}
+
+fn log_value<'a>(line: Option<&'a str>, key: &str) -> Result<&'a str> {
+	match line.and_then(|line| line.split_once(": ")) {
+		Some((line_key, value)) if line_key == key => Ok(value.trim()),
+		_ => Err(invalid_log(&format!("missing {}", key))),
+	}
+}
+
+fn invalid_log(reason: &str) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to parse hash log; {}", reason))
+}
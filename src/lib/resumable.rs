@@ -0,0 +1,667 @@
+/*************************************************************************
+* ph0llux:0f6c2d9a84e13b57c0d2a9f6e41b83c7d5a0e29f1b6c48d37e0a5f92c6b1d48e
+*************************************************************************/
+//!resumable Module (hashing, which can be interrupted and resumed from a serializable checkpoint).
+
+// - STD
+#[cfg(feature = "blake3")]
+use std::convert::TryInto;
+use std::fmt;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::str::FromStr;
+
+// - internal
+use super::{Digest, HashAlgorithm, PhollaitsError, PhollaitsErrorKind, Result, DEFAULT_HASH_BUFFER_SIZE};
+
+// - external
+use data_encoding::{HEXLOWER, HEXLOWER_PERMISSIVE};
+#[cfg(feature = "blake3")]
+use blake3::hazmat::{merge_subtrees_non_root, merge_subtrees_root, HasherExt, Mode};
+
+const MD5_INIT: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+const SHA1_INIT: [u32; 5] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476, 0xc3d2_e1f0];
+const SHA256_INIT: [u32; 8] = [
+	0x6a09_e667, 0xbb67_ae85, 0x3c6e_f372, 0xa54f_f53a, 0x510e_527f, 0x9b05_688c, 0x1f83_d9ab, 0x5be0_cd19,
+];
+const SHA384_INIT: [u64; 8] = [
+	0xcbbb_9d5d_c105_9ed8, 0x629a_292a_367c_d507, 0x9159_015a_3070_dd17, 0x152f_ecd8_f70e_5939,
+	0x6733_2667_ffc0_0b31, 0x8eb4_4a87_6858_1511, 0xdb0c_2e0d_64f9_8fa7, 0x47b5_481d_befa_4fa4,
+];
+const SHA512_INIT: [u64; 8] = [
+	0x6a09_e667_f3bc_c908, 0xbb67_ae85_84ca_a73b, 0x3c6e_f372_fe94_f82b, 0xa54f_f53a_5f1d_36f1,
+	0x510e_527f_ade6_82d1, 0x9b05_688c_2b3e_6c1f, 0x1f83_d9ab_fb41_bd6b, 0x5be0_cd19_137e_2179,
+];
+
+const MD5_SHIFTS: [u32; 64] = [
+	7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+	5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+	4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+	6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+const MD5_CONSTANTS: [u32; 64] = [
+	0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+	0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+	0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+	0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+	0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+	0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+	0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+	0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+#[cfg(feature = "blake2")]
+const BLAKE2_SIGMA: [[usize; 16]; 10] = [
+	[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+	[14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+	[11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+	[7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+	[9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+	[2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+	[12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+	[13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+	[6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+	[10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+// the size of the subtrees, which are hashed at once by BLAKE3 (16 chunks).
+#[cfg(feature = "blake3")]
+const BLAKE3_SUBTREE_LEN: usize = 16 * blake3::CHUNK_LEN;
+
+/// A checkpoint of a [ResumableHasher]: the algorithm, the number of hashed bytes and the internal state. It can be
+/// serialized to and parsed from a single line of text ("algorithm:offset:state:buffer", with hex encoded state and
+/// buffer).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashCheckpoint {
+	algorithm: HashAlgorithm,
+	offset: u64,
+	state: Vec<u8>,
+	buffer: Vec<u8>,
+}
+
+impl HashCheckpoint {
+	/// returns the algorithm.
+	pub fn algorithm(&self) -> HashAlgorithm {
+		self.algorithm
+	}
+
+	/// returns the number of bytes, which were hashed before the checkpoint (i.e. the offset to resume from).
+	pub fn offset(&self) -> u64 {
+		self.offset
+	}
+}
+
+impl fmt::Display for HashCheckpoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}:{}:{}", self.algorithm, self.offset, HEXLOWER.encode(&self.state), HEXLOWER.encode(&self.buffer))
+	}
+}
+
+impl FromStr for HashCheckpoint {
+	type Err = PhollaitsError;
+
+	fn from_str(s: &str) -> Result<HashCheckpoint> {
+		let parts: Vec<&str> = s.trim().split(':').collect();
+		if parts.len() != 4 {
+			return Err(invalid_checkpoint("expected algorithm:offset:state:buffer"));
+		}
+		let decode = |hex: &str| HEXLOWER_PERMISSIVE.decode(hex.as_bytes()).map_err(|e| invalid_checkpoint(&e.to_string()));
+		let checkpoint = HashCheckpoint {
+			algorithm: parts[0].parse()?,
+			offset: parts[1].parse()?,
+			state: decode(parts[2])?,
+			buffer: decode(parts[3])?,
+		};
+		// validates the state.
+		ResumableHasher::from_checkpoint(&checkpoint)?;
+		Ok(checkpoint)
+	}
+}
+
+/// A hasher for all [HashAlgorithm]s, whose state can be saved as a [HashCheckpoint] at any time. Hashing (e.g. of a
+/// large image) can then be resumed later from the offset of the checkpoint, instead of starting over.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+///
+/// fn main() -> Result<()> {
+/// 	let data = std::fs::read("assets/example.jpg").unwrap();
+/// 	for algorithm in HashAlgorithm::all() {
+/// 		// hash the first part and save the checkpoint (e.g. to a file).
+/// 		let mut hasher = ResumableHasher::new(*algorithm);
+/// 		hasher.update(&data[..30001]);
+/// 		let saved = hasher.checkpoint().to_string();
+///
+/// 		// resume later from the offset of the checkpoint.
+/// 		let checkpoint: HashCheckpoint = saved.parse()?;
+/// 		let mut hasher = ResumableHasher::from_checkpoint(&checkpoint)?;
+/// 		hasher.update(&data[checkpoint.offset() as usize..]);
+/// 		assert_eq!(hasher.finalize(), data.digest_with(*algorithm)?);
+/// 	}
+/// 	Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct ResumableHasher {
+	algorithm: HashAlgorithm,
+	offset: u64,
+	state: State,
+	// the bytes of the current (incomplete) block.
+	buffer: Vec<u8>,
+}
+
+#[derive(Clone)]
+enum State {
+	Md5([u32; 4]),
+	Sha1([u32; 5]),
+	Sha256([u32; 8]),
+	Sha512([u64; 8]),
+	#[cfg(feature = "sha3")]
+	Sha3([u64; 25]),
+	#[cfg(feature = "blake2")]
+	Blake2b([u64; 8]),
+	#[cfg(feature = "blake2")]
+	Blake2s([u32; 8]),
+	// the chaining values of the completed subtrees.
+	#[cfg(feature = "blake3")]
+	Blake3(Vec<[u8; 32]>),
+}
+
+impl ResumableHasher {
+	/// returns a new hasher.
+	pub fn new(algorithm: HashAlgorithm) -> ResumableHasher {
+		let state = match algorithm {
+			HashAlgorithm::Md5 => State::Md5(MD5_INIT),
+			HashAlgorithm::Sha1 => State::Sha1(SHA1_INIT),
+			HashAlgorithm::Sha256 => State::Sha256(SHA256_INIT),
+			HashAlgorithm::Sha384 => State::Sha512(SHA384_INIT),
+			HashAlgorithm::Sha512 => State::Sha512(SHA512_INIT),
+			#[cfg(feature = "sha3")]
+			HashAlgorithm::Sha3_224 | HashAlgorithm::Sha3_256 | HashAlgorithm::Sha3_384 | HashAlgorithm::Sha3_512 => State::Sha3([0; 25]),
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2b => {
+				let mut h = SHA512_INIT;
+				// no key, 64 byte output.
+				h[0] ^= 0x0101_0040;
+				State::Blake2b(h)
+			},
+			#[cfg(feature = "blake2")]
+			HashAlgorithm::Blake2s => {
+				let mut h = SHA256_INIT;
+				// no key, 32 byte output.
+				h[0] ^= 0x0101_0020;
+				State::Blake2s(h)
+			},
+			#[cfg(feature = "blake3")]
+			HashAlgorithm::Blake3 => State::Blake3(Vec::new()),
+		};
+		ResumableHasher {
+			algorithm,
+			offset: 0,
+			buffer: Vec::with_capacity(block_len(algorithm)),
+			state,
+		}
+	}
+
+	/// returns a hasher with the state of the checkpoint.
+	pub fn from_checkpoint(checkpoint: &HashCheckpoint) -> Result<ResumableHasher> {
+		let algorithm = checkpoint.algorithm;
+		let mut hasher = ResumableHasher::new(algorithm);
+		let block_len = block_len(algorithm) as u64;
+		let expected_buffer_len = if is_lazy(algorithm) && checkpoint.offset > 0 {
+			(checkpoint.offset - 1) % block_len + 1
+		} else {
+			checkpoint.offset % block_len
+		};
+		if checkpoint.buffer.len() as u64 != expected_buffer_len {
+			return Err(invalid_checkpoint("the length of the buffer does not match the offset"));
+		}
+		let state = &checkpoint.state[..];
+		let valid = match &mut hasher.state {
+			State::Md5(h) => read_u32_words(state, h),
+			State::Sha1(h) => read_u32_words(state, h),
+			State::Sha256(h) => read_u32_words(state, h),
+			State::Sha512(h) => read_u64_words(state, h),
+			#[cfg(feature = "sha3")]
+			State::Sha3(h) => read_u64_words(state, h),
+			#[cfg(feature = "blake2")]
+			State::Blake2b(h) => read_u64_words(state, h),
+			#[cfg(feature = "blake2")]
+			State::Blake2s(h) => read_u32_words(state, h),
+			#[cfg(feature = "blake3")]
+			State::Blake3(stack) => {
+				let subtrees = (checkpoint.offset - expected_buffer_len) / block_len;
+				*stack = state.chunks(32).filter_map(|cv| cv.try_into().ok()).collect();
+				state.len() == 32 * subtrees.count_ones() as usize
+			},
+		};
+		if !valid {
+			return Err(invalid_checkpoint("the length of the state does not match the algorithm"));
+		}
+		hasher.offset = checkpoint.offset;
+		hasher.buffer.extend_from_slice(&checkpoint.buffer);
+		Ok(hasher)
+	}
+
+	/// returns the algorithm.
+	pub fn algorithm(&self) -> HashAlgorithm {
+		self.algorithm
+	}
+
+	/// returns the number of bytes, which were hashed so far.
+	pub fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	/// feeds the data into the hasher.
+	pub fn update(&mut self, mut data: &[u8]) {
+		let block_len = block_len(self.algorithm);
+		let lazy = is_lazy(self.algorithm);
+		while !data.is_empty() {
+			if self.buffer.len() == block_len {
+				// the buffered block of a lazy algorithm is not the last block anymore.
+				self.process_buffer();
+			}
+			let count = (block_len - self.buffer.len()).min(data.len());
+			self.buffer.extend_from_slice(&data[..count]);
+			self.offset += count as u64;
+			data = &data[count..];
+			if !lazy && self.buffer.len() == block_len {
+				self.process_buffer();
+			}
+		}
+	}
+
+	/// returns a checkpoint of the current state.
+	pub fn checkpoint(&self) -> HashCheckpoint {
+		let state = match &self.state {
+			State::Md5(h) => u32_words_to_bytes(h),
+			State::Sha1(h) => u32_words_to_bytes(h),
+			State::Sha256(h) => u32_words_to_bytes(h),
+			State::Sha512(h) => u64_words_to_bytes(h),
+			#[cfg(feature = "sha3")]
+			State::Sha3(h) => u64_words_to_bytes(h),
+			#[cfg(feature = "blake2")]
+			State::Blake2b(h) => u64_words_to_bytes(h),
+			#[cfg(feature = "blake2")]
+			State::Blake2s(h) => u32_words_to_bytes(h),
+			#[cfg(feature = "blake3")]
+			State::Blake3(stack) => stack.concat(),
+		};
+		HashCheckpoint {
+			algorithm: self.algorithm,
+			offset: self.offset,
+			state,
+			buffer: self.buffer.clone(),
+		}
+	}
+
+	/// hashes the reader from the offset of the hasher on (the reader will be seeked to this offset) and passes a
+	/// checkpoint to the callback after every interval of bytes. Returns the digest of the whole input.
+	/// # Example
+	/// ```
+	/// extern crate phollaits;
+	/// use phollaits::*;
+	/// use std::fs::File;
+	///
+	/// fn main() -> Result<()> {
+	/// 	let mut checkpoints = Vec::new();
+	/// 	let mut file = File::open("assets/example.jpg").unwrap();
+	/// 	let hasher = ResumableHasher::new(HashAlgorithm::Sha256);
+	/// 	let digest = hasher.hash_reader(&mut file, 16 * 1024, |checkpoint| {
+	/// 		checkpoints.push(checkpoint.to_string());
+	/// 		Ok(())
+	/// 	})?;
+	/// 	assert_eq!(checkpoints.len(), 4);
+	///
+	/// 	// resume from the second checkpoint.
+	/// 	let checkpoint: HashCheckpoint = checkpoints[1].parse()?;
+	/// 	assert_eq!(checkpoint.offset(), 32 * 1024);
+	/// 	let hasher = ResumableHasher::from_checkpoint(&checkpoint)?;
+	/// 	let mut file = File::open("assets/example.jpg").unwrap();
+	/// 	assert_eq!(hasher.hash_reader(&mut file, 16 * 1024, |_| Ok(()))?, digest);
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn hash_reader<R, F>(mut self, reader: &mut R, checkpoint_interval: u64, mut callback: F) -> Result<Digest>
+	where
+		R: Read + Seek,
+		F: FnMut(&HashCheckpoint) -> Result<()>,
+	{
+		let checkpoint_interval = checkpoint_interval.max(1);
+		if let Err(e) = reader.seek(SeekFrom::Start(self.offset)) {
+			return Err(resumable_error(e));
+		}
+		let mut buffer = vec![0; DEFAULT_HASH_BUFFER_SIZE];
+		loop {
+			// the reads stop at the next checkpoint.
+			let until_checkpoint = checkpoint_interval - self.offset % checkpoint_interval;
+			let wanted = (buffer.len() as u64).min(until_checkpoint) as usize;
+			let count = match reader.read(&mut buffer[..wanted]) {
+				Ok(x) => x,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+				Err(e) => return Err(resumable_error(e)),
+			};
+			if count == 0 {
+				break;
+			}
+			self.update(&buffer[..count]);
+			if self.offset % checkpoint_interval == 0 {
+				callback(&self.checkpoint())?;
+			}
+		}
+		Ok(self.finalize())
+	}
+
+	/// returns the digest of all hashed bytes.
+	pub fn finalize(mut self) -> Digest {
+		let algorithm = self.algorithm;
+		let buffer = std::mem::take(&mut self.buffer);
+		let bytes = match self.state {
+			State::Md5(mut h) => {
+				for block in md_padding(&buffer, self.offset, 64, false).chunks(64) {
+					md5_compress(&mut h, block);
+				}
+				h.iter().flat_map(|word| word.to_le_bytes()).collect()
+			},
+			State::Sha1(mut h) => {
+				for block in md_padding(&buffer, self.offset, 64, true).chunks(64) {
+					sha1::compress(&mut h, std::slice::from_ref(block.into()));
+				}
+				u32_words_to_bytes(&h)
+			},
+			State::Sha256(mut h) => {
+				for block in md_padding(&buffer, self.offset, 64, true).chunks(64) {
+					sha2::compress256(&mut h, std::slice::from_ref(block.into()));
+				}
+				u32_words_to_bytes(&h)
+			},
+			State::Sha512(mut h) => {
+				for block in md_padding(&buffer, self.offset, 128, true).chunks(128) {
+					sha2::compress512(&mut h, std::slice::from_ref(block.into()));
+				}
+				u64_words_to_bytes(&h)
+			},
+			#[cfg(feature = "sha3")]
+			State::Sha3(mut h) => {
+				let rate = block_len(algorithm);
+				let mut block = buffer;
+				block.push(0x06);
+				block.resize(rate, 0);
+				block[rate - 1] |= 0x80;
+				sha3_absorb(&mut h, &block);
+				h.iter().flat_map(|lane| lane.to_le_bytes()).collect()
+			},
+			#[cfg(feature = "blake2")]
+			State::Blake2b(mut h) => {
+				let mut block = buffer;
+				block.resize(128, 0);
+				blake2b_compress(&mut h, &block, u128::from(self.offset), true);
+				h.iter().flat_map(|word| word.to_le_bytes()).collect()
+			},
+			#[cfg(feature = "blake2")]
+			State::Blake2s(mut h) => {
+				let mut block = buffer;
+				block.resize(64, 0);
+				blake2s_compress(&mut h, &block, self.offset, true);
+				h.iter().flat_map(|word| word.to_le_bytes()).collect()
+			},
+			#[cfg(feature = "blake3")]
+			State::Blake3(mut stack) => {
+				if stack.is_empty() {
+					blake3::hash(&buffer).as_bytes().to_vec()
+				} else {
+					// the last subtree is merged with the completed subtrees from right to left.
+					let mut hasher = blake3::Hasher::new();
+					hasher.set_input_offset(self.offset - buffer.len() as u64);
+					hasher.update(&buffer);
+					let mut cv = hasher.finalize_non_root();
+					while stack.len() > 1 {
+						let left = stack.pop().unwrap_or_default();
+						cv = merge_subtrees_non_root(&left, &cv, Mode::Hash);
+					}
+					let left = stack.pop().unwrap_or_default();
+					merge_subtrees_root(&left, &cv, Mode::Hash).as_bytes().to_vec()
+				}
+			},
+		};
+		let mut bytes: Vec<u8> = bytes;
+		bytes.truncate(algorithm.digest_len());
+		Digest::from_parts(algorithm, bytes)
+	}
+
+	// processes the full buffer.
+	fn process_buffer(&mut self) {
+		// the number of bytes before the block.
+		#[cfg(any(feature = "blake2", feature = "blake3"))]
+		let processed = self.offset - self.buffer.len() as u64;
+		let block = &self.buffer[..];
+		match &mut self.state {
+			State::Md5(h) => md5_compress(h, block),
+			State::Sha1(h) => sha1::compress(h, std::slice::from_ref(block.into())),
+			State::Sha256(h) => sha2::compress256(h, std::slice::from_ref(block.into())),
+			State::Sha512(h) => sha2::compress512(h, std::slice::from_ref(block.into())),
+			#[cfg(feature = "sha3")]
+			State::Sha3(h) => sha3_absorb(h, block),
+			#[cfg(feature = "blake2")]
+			State::Blake2b(h) => blake2b_compress(h, block, u128::from(processed) + 128, false),
+			#[cfg(feature = "blake2")]
+			State::Blake2s(h) => blake2s_compress(h, block, processed + 64, false),
+			#[cfg(feature = "blake3")]
+			State::Blake3(stack) => {
+				let mut hasher = blake3::Hasher::new();
+				hasher.set_input_offset(processed);
+				hasher.update(block);
+				let mut cv = hasher.finalize_non_root();
+				// merges the completed subtrees like a binary counter.
+				let mut subtrees = processed / BLAKE3_SUBTREE_LEN as u64 + 1;
+				while subtrees & 1 == 0 {
+					let left = stack.pop().unwrap_or_default();
+					cv = merge_subtrees_non_root(&left, &cv, Mode::Hash);
+					subtrees >>= 1;
+				}
+				stack.push(cv);
+			},
+		}
+		self.buffer.clear();
+	}
+}
+
+// the length of the blocks, which are processed at once.
+fn block_len(algorithm: HashAlgorithm) -> usize {
+	match algorithm {
+		HashAlgorithm::Md5 | HashAlgorithm::Sha1 | HashAlgorithm::Sha256 => 64,
+		HashAlgorithm::Sha384 | HashAlgorithm::Sha512 => 128,
+		// the rate of the sponge.
+		#[cfg(feature = "sha3")]
+		HashAlgorithm::Sha3_224 | HashAlgorithm::Sha3_256 | HashAlgorithm::Sha3_384 | HashAlgorithm::Sha3_512 => 200 - 2 * algorithm.digest_len(),
+		#[cfg(feature = "blake2")]
+		HashAlgorithm::Blake2b => 128,
+		#[cfg(feature = "blake2")]
+		HashAlgorithm::Blake2s => 64,
+		#[cfg(feature = "blake3")]
+		HashAlgorithm::Blake3 => BLAKE3_SUBTREE_LEN,
+	}
+}
+
+// the last block of a lazy algorithm is finalized differently, so a full block is only processed when more data follows.
+fn is_lazy(algorithm: HashAlgorithm) -> bool {
+	match algorithm {
+		#[cfg(feature = "blake2")]
+		HashAlgorithm::Blake2b | HashAlgorithm::Blake2s => true,
+		#[cfg(feature = "blake3")]
+		HashAlgorithm::Blake3 => true,
+		_ => false,
+	}
+}
+
+// the padding of MD5, SHA-1 and SHA-2: 0x80, zeros and the length in bits.
+fn md_padding(buffer: &[u8], length: u64, block_len: usize, big_endian: bool) -> Vec<u8> {
+	let length_len = block_len / 8;
+	let mut padded = buffer.to_vec();
+	padded.push(0x80);
+	while (padded.len() + length_len) % block_len != 0 {
+		padded.push(0);
+	}
+	let bits = u128::from(length) * 8;
+	if big_endian {
+		padded.extend_from_slice(&bits.to_be_bytes()[16 - length_len..]);
+	} else {
+		padded.extend_from_slice(&(bits as u64).to_le_bytes());
+	}
+	padded
+}
+
+fn md5_compress(state: &mut [u32; 4], block: &[u8]) {
+	let words: Vec<u32> = block.chunks(4).map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]])).collect();
+	let [mut a, mut b, mut c, mut d] = *state;
+	for i in 0..64 {
+		let (f, g) = match i / 16 {
+			0 => ((b & c) | (!b & d), i),
+			1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+			2 => (b ^ c ^ d, (3 * i + 5) % 16),
+			_ => (c ^ (b | !d), (7 * i) % 16),
+		};
+		let f = f.wrapping_add(a).wrapping_add(MD5_CONSTANTS[i]).wrapping_add(words[g]);
+		a = d;
+		d = c;
+		c = b;
+		b = b.wrapping_add(f.rotate_left(MD5_SHIFTS[i]));
+	}
+	state[0] = state[0].wrapping_add(a);
+	state[1] = state[1].wrapping_add(b);
+	state[2] = state[2].wrapping_add(c);
+	state[3] = state[3].wrapping_add(d);
+}
+
+#[cfg(feature = "sha3")]
+fn sha3_absorb(state: &mut [u64; 25], block: &[u8]) {
+	for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+		let mut lane_bytes = [0; 8];
+		lane_bytes.copy_from_slice(bytes);
+		*lane ^= u64::from_le_bytes(lane_bytes);
+	}
+	keccak::f1600(state);
+}
+
+#[cfg(feature = "blake2")]
+fn blake2b_compress(h: &mut [u64; 8], block: &[u8], counter: u128, last: bool) {
+	fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+		v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+		v[d] = (v[d] ^ v[a]).rotate_right(32);
+		v[c] = v[c].wrapping_add(v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(24);
+		v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+		v[d] = (v[d] ^ v[a]).rotate_right(16);
+		v[c] = v[c].wrapping_add(v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(63);
+	}
+	let mut m = [0u64; 16];
+	for (word, bytes) in m.iter_mut().zip(block.chunks(8)) {
+		let mut word_bytes = [0; 8];
+		word_bytes.copy_from_slice(bytes);
+		*word = u64::from_le_bytes(word_bytes);
+	}
+	let mut v = [0u64; 16];
+	v[..8].copy_from_slice(h);
+	v[8..].copy_from_slice(&SHA512_INIT);
+	v[12] ^= counter as u64;
+	v[13] ^= (counter >> 64) as u64;
+	if last {
+		v[14] = !v[14];
+	}
+	for round in 0..12 {
+		let s = &BLAKE2_SIGMA[round % 10];
+		g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+		g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+		g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+		g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+		g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+		g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+		g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+		g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+	}
+	for i in 0..8 {
+		h[i] ^= v[i] ^ v[i + 8];
+	}
+}
+
+#[cfg(feature = "blake2")]
+fn blake2s_compress(h: &mut [u32; 8], block: &[u8], counter: u64, last: bool) {
+	fn g(v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32) {
+		v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+		v[d] = (v[d] ^ v[a]).rotate_right(16);
+		v[c] = v[c].wrapping_add(v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(12);
+		v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+		v[d] = (v[d] ^ v[a]).rotate_right(8);
+		v[c] = v[c].wrapping_add(v[d]);
+		v[b] = (v[b] ^ v[c]).rotate_right(7);
+	}
+	let mut m = [0u32; 16];
+	for (word, bytes) in m.iter_mut().zip(block.chunks(4)) {
+		*word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+	}
+	let mut v = [0u32; 16];
+	v[..8].copy_from_slice(h);
+	v[8..].copy_from_slice(&SHA256_INIT);
+	v[12] ^= counter as u32;
+	v[13] ^= (counter >> 32) as u32;
+	if last {
+		v[14] = !v[14];
+	}
+	for s in &BLAKE2_SIGMA {
+		g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+		g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+		g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+		g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+		g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+		g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+		g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+		g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+	}
+	for i in 0..8 {
+		h[i] ^= v[i] ^ v[i + 8];
+	}
+}
+
+fn u32_words_to_bytes(words: &[u32]) -> Vec<u8> {
+	words.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
+
+fn u64_words_to_bytes(words: &[u64]) -> Vec<u8> {
+	words.iter().flat_map(|word| word.to_be_bytes()).collect()
+}
+
+// returns false, if the length of the bytes does not match the words.
+fn read_u32_words(bytes: &[u8], words: &mut [u32]) -> bool {
+	if bytes.len() != words.len() * 4 {
+		return false;
+	}
+	for (word, bytes) in words.iter_mut().zip(bytes.chunks(4)) {
+		*word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+	}
+	true
+}
+
+fn read_u64_words(bytes: &[u8], words: &mut [u64]) -> bool {
+	if bytes.len() != words.len() * 8 {
+		return false;
+	}
+	for (word, bytes) in words.iter_mut().zip(bytes.chunks(8)) {
+		let mut word_bytes = [0; 8];
+		word_bytes.copy_from_slice(bytes);
+		*word = u64::from_be_bytes(word_bytes);
+	}
+	true
+}
+
+fn invalid_checkpoint(reason: &str) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to parse hash checkpoint; {}", reason))
+}
+
+fn resumable_error(e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(PhollaitsErrorKind::HashingError, format!("Error while trying to hash input; {}", e))
+}
@@ -0,0 +1,248 @@
+/*************************************************************************
+* ph0llux:d6a3f0e58b21c47d9e0a5b3c18f62d07e94b1a5c3d8f02e67b4a19c5d0e3f782
+*************************************************************************/
+//!search Module (search for byte patterns inside of archive entries, without extracting them).
+
+// - STD
+use std::io;
+use std::path::PathBuf;
+
+// - internal
+use super::{auto_decompress, PhollaitsError, PhollaitsErrorKind, Result};
+
+// - external
+use tar::{Archive, EntryType};
+
+const SEARCH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A pattern for [search_archive].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchPattern {
+	/// matches exactly the given bytes.
+	Literal(Vec<u8>),
+	/// matches the given text, ignoring the case of ASCII characters.
+	CaseInsensitive(String),
+	/// matches a sequence of bytes, where None matches any byte (see [SearchPattern::wildcard]).
+	Wildcard(Vec<Option<u8>>),
+}
+
+impl SearchPattern {
+	/// parses a wildcard pattern of hex bytes (whitespace is ignored), where "??" matches any byte.
+	/// # Example
+	/// ```
+	/// use phollaits::{SearchPattern, Result};
+	///
+	/// fn main() -> Result<()> {
+	/// 	let pattern = SearchPattern::wildcard("ff d8 ?? e0")?;
+	/// 	assert_eq!(pattern, SearchPattern::Wildcard(vec![Some(0xff), Some(0xd8), None, Some(0xe0)]));
+	/// 	Ok(())
+	/// }
+	/// ```
+	pub fn wildcard(pattern: &str) -> Result<SearchPattern> {
+		let digits: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+		if digits.len() % 2 != 0 {
+			return Err(PhollaitsError::new(
+				PhollaitsErrorKind::ArchiveError,
+				format!("Invalid wildcard pattern {}; odd number of hex digits", pattern)))
+		}
+		let mut bytes = Vec::new();
+		for pair in digits.chunks(2) {
+			let pair: String = pair.iter().collect();
+			if pair == "??" {
+				bytes.push(None);
+			} else {
+				bytes.push(Some(u8::from_str_radix(&pair, 16)?));
+			}
+		}
+		Ok(SearchPattern::Wildcard(bytes))
+	}
+
+	/// returns the length of the pattern in bytes.
+	pub fn len(&self) -> usize {
+		match self {
+			SearchPattern::Literal(x) => x.len(),
+			SearchPattern::CaseInsensitive(x) => x.len(),
+			SearchPattern::Wildcard(x) => x.len(),
+		}
+	}
+
+	/// returns true, if the pattern is empty.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	// the data must have the length of the pattern.
+	fn matches(&self, data: &[u8]) -> bool {
+		match self {
+			SearchPattern::Literal(x) => x[..] == *data,
+			SearchPattern::CaseInsensitive(x) => x.as_bytes().eq_ignore_ascii_case(data),
+			SearchPattern::Wildcard(x) => x.iter().zip(data).all(|(p, b)| p.is_none() || *p == Some(*b)),
+		}
+	}
+}
+
+/// The options for [search_archive].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+	/// the number of bytes before and after a match, which will be returned as context (default: 16).
+	pub context: usize,
+	/// entries larger than this size (in bytes) will be skipped (default: None).
+	pub max_entry_size: Option<u64>,
+}
+
+impl Default for SearchOptions {
+	fn default() -> SearchOptions {
+		SearchOptions {
+			context: 16,
+			max_entry_size: None,
+		}
+	}
+}
+
+/// A match found by [search_archive].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchMatch {
+	/// the path of the entry in the archive.
+	pub path: PathBuf,
+	/// the offset of the match inside of the entry.
+	pub offset: u64,
+	/// the matched bytes including the context before and after.
+	pub context: Vec<u8>,
+	/// the offset of the context inside of the entry.
+	pub context_offset: u64,
+}
+
+/// The result of [search_archive].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchReport {
+	/// all matches, in the order of the archive entries.
+	pub matches: Vec<SearchMatch>,
+	/// the entries which were skipped, as they are larger than [SearchOptions::max_entry_size].
+	pub skipped_entries: Vec<PathBuf>,
+}
+
+/// searches the pattern in all regular files of a (gzip or zstd compressed, see [auto_decompress]) tar archive. The
+/// entries are streamed one after another, so nothing has to be extracted. Overlapping matches will be reported.
+/// # Example
+/// ```
+/// extern crate tar;
+/// extern crate phollaits;
+///
+/// use phollaits::*;
+/// use tar::Builder;
+/// use std::path::PathBuf;
+///
+/// fn main() -> Result<()> {
+/// 	let mut archive = Vec::new();
+/// 	let mut b = Builder::new(&mut archive);
+/// 	b.append_text("notes.txt", "the Password is hunter2")?;
+/// 	b.append_file_directly("assets/example.jpg")?;
+/// 	b.close_archive()?;
+///
+/// 	let options = SearchOptions { context: 3, ..Default::default() };
+/// 	let pattern = SearchPattern::CaseInsensitive("password".to_string());
+/// 	let report = search_archive(&archive[..], &pattern, &options)?;
+/// 	assert_eq!(report.matches.len(), 1);
+/// 	assert_eq!(report.matches[0].path, PathBuf::from("notes.txt"));
+/// 	assert_eq!(report.matches[0].offset, 4);
+/// 	assert_eq!(report.matches[0].context, b"he Password is".to_vec());
+///
+/// 	// the JPEG start of image marker.
+/// 	let pattern = SearchPattern::wildcard("ff d8 ff ??")?;
+/// 	let report = search_archive(&archive[..], &pattern, &options)?;
+/// 	assert_eq!(report.matches[0].path, PathBuf::from("assets/example.jpg"));
+/// 	assert_eq!(report.matches[0].offset, 0);
+/// 	Ok(())
+/// }
+/// ```
+pub fn search_archive<R: io::Read>(reader: R, pattern: &SearchPattern, options: &SearchOptions) -> Result<SearchReport> {
+	let mut report = SearchReport::default();
+	let skipped = search_archive_with(reader, pattern, options, |m| report.matches.push(m))?;
+	report.skipped_entries = skipped;
+	Ok(report)
+}
+
+/// like [search_archive], but every match will be passed to the callback as soon as it is found. Returns the skipped
+/// entries.
+pub fn search_archive_with<R: io::Read, F: FnMut(SearchMatch)>(
+	reader: R,
+	pattern: &SearchPattern,
+	options: &SearchOptions,
+	mut callback: F) -> Result<Vec<PathBuf>> {
+	if pattern.is_empty() {
+		return Err(PhollaitsError::new(PhollaitsErrorKind::ArchiveError, "Error while trying to search; empty pattern"));
+	}
+	let mut skipped = Vec::new();
+	let mut archive = Archive::new(auto_decompress(reader)?);
+	let entries = match archive.entries() {
+		Ok(x) => x,
+		Err(e) => return Err(search_error(e))
+	};
+	for entry in entries {
+		let mut entry = entry.map_err(search_error)?;
+		match entry.header().entry_type() {
+			EntryType::Regular | EntryType::Continuous => (),
+			_ => continue,
+		}
+		let path = entry.path().map_err(search_error)?.into_owned();
+		if let Some(max_entry_size) = options.max_entry_size {
+			if entry.size() > max_entry_size {
+				skipped.push(path);
+				continue;
+			}
+		}
+		search_reader(&mut entry, pattern, options.context, |offset, context_offset, context| callback(SearchMatch {
+			path: path.clone(),
+			offset,
+			context,
+			context_offset,
+		}))?;
+	}
+	Ok(skipped)
+}
+
+// searches the pattern in a stream. Only the bytes needed for the context and the pattern will be kept in memory.
+fn search_reader<R: io::Read, F: FnMut(u64, u64, Vec<u8>)>(
+	reader: &mut R,
+	pattern: &SearchPattern,
+	context: usize,
+	mut callback: F) -> Result<()> {
+	let pattern_len = pattern.len() as u64;
+	let context = context as u64;
+	let mut buffer = vec![0; SEARCH_BUFFER_SIZE];
+	let mut window = Vec::new();
+	let mut window_start = 0u64;
+	let mut next_offset = 0u64;
+	loop {
+		let count = match reader.read(&mut buffer) {
+			Ok(x) => x,
+			Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+			Err(e) => return Err(search_error(e))
+		};
+		let eof = count == 0;
+		window.extend_from_slice(&buffer[..count]);
+		let window_end = window_start + window.len() as u64;
+		// a match can only be reported if the context after the match was already read (or the end was reached).
+		let needed_after = if eof { 0 } else { context };
+		while next_offset + pattern_len + needed_after <= window_end {
+			let start = (next_offset - window_start) as usize;
+			if pattern.matches(&window[start..start + pattern_len as usize]) {
+				let context_offset = next_offset.saturating_sub(context).max(window_start);
+				let context_end = (next_offset + pattern_len + context).min(window_end);
+				let context_bytes = window[(context_offset - window_start) as usize..(context_end - window_start) as usize].to_vec();
+				callback(next_offset, context_offset, context_bytes);
+			}
+			next_offset += 1;
+		}
+		if eof {
+			return Ok(());
+		}
+		let keep_from = next_offset.saturating_sub(context).max(window_start);
+		window.drain(..(keep_from - window_start) as usize);
+		window_start = keep_from;
+	}
+}
+
+fn search_error(e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(PhollaitsErrorKind::ArchiveError, format!("Error while trying to search archive; {}", e))
+}
\ No newline at end of file
@@ -0,0 +1,201 @@
+/*************************************************************************
+* ph0llux:a81f5c3e07d92b64f1e8c05a3d7b29e16f40c8d53a2e97b10d6f48c2e93a7b05
+*************************************************************************/
+//!treehash Module (a deterministic digest of a whole directory tree).
+
+// - STD
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// - internal
+use super::{file_permissions, Digest, HashAlgorithm, HashExt, Hasher, PhollaitsError, PhollaitsErrorKind, Result};
+
+/// The type of a [TreeNode].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeNodeType {
+	File,
+	Directory,
+	Symlink,
+}
+
+impl TreeNodeType {
+	// the type byte of the encoding (see [tree_hash]).
+	fn type_byte(self) -> u8 {
+		match self {
+			TreeNodeType::File => b'f',
+			TreeNodeType::Directory => b'd',
+			TreeNodeType::Symlink => b'l',
+		}
+	}
+}
+
+/// The digest of a single file, directory or symbolic link of a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+	/// the path relative to the root of the tree (the root itself has an empty path).
+	pub path: PathBuf,
+	pub node_type: TreeNodeType,
+	pub digest: Digest,
+}
+
+/// The options for [tree_hash].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeHashOptions {
+	/// the permissions of files and directories will be part of the digest (default: false).
+	pub include_permissions: bool,
+	/// the digests of all nodes will be returned in [TreeHash::nodes] (default: false).
+	pub collect_nodes: bool,
+}
+
+/// The result of [tree_hash].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeHash {
+	/// the digest of the root.
+	pub digest: Digest,
+	/// the digests of all nodes (only if [TreeHashOptions::collect_nodes] is set), in canonical order: every directory is
+	/// followed by its children, sorted by name.
+	pub nodes: Vec<TreeNode>,
+}
+
+/// calculates a Merkle digest of the directory tree (or of a single file). Two trees have the same digest, if they
+/// contain the same names, types and contents (and permissions, if [TreeHashOptions::include_permissions] is set).
+/// Timestamps, owners and the name of the root are not part of the digest.
+///
+/// The digests of the nodes are calculated as follows (H is the hash function of the given algorithm):
+/// - file: H(content), i.e. the same digest as e.g. by sha256sum.
+/// - symbolic link: H(target), where the target uses "/" as separator. Symbolic links will never be followed.
+/// - directory: H(record_1 || ... || record_n) with one record per child, sorted bytewise by name. A record is
+///   type || len(name) || name || mode || digest, where type is one byte ("f", "d" or "l"), len(name) is the length of
+///   the name in bytes as big-endian u64, name is the UTF-8 encoded (on Unix: the raw) name, mode is the permission bits
+///   (mode & 0o7777) as big-endian u32 (only for files and directories and only if the permissions are included) and
+///   digest is the digest of the child.
+///
+/// Other file types (e.g. sockets or devices) are skipped.
+/// # Example
+/// ```
+/// extern crate phollaits;
+/// use phollaits::*;
+/// use std::path::PathBuf;
+///
+/// fn main() -> Result<()> {
+/// 	let options = TreeHashOptions { collect_nodes: true, ..Default::default() };
+/// 	let tree = tree_hash("assets", HashAlgorithm::Sha256, &options)?;
+/// 	assert_eq!(tree.digest.to_hex(), "02fa9f8e341360152a097cd6f07fa7686e72531f7e633dbb3b54f119e4cd5816");
+///
+/// 	assert_eq!(tree.nodes.len(), 3);
+/// 	assert_eq!(tree.nodes[0].path, PathBuf::new());
+/// 	assert_eq!(tree.nodes[1].path, PathBuf::from("example.jpg"));
+/// 	assert_eq!(tree.nodes[1].node_type, TreeNodeType::File);
+/// 	assert_eq!(tree.nodes[1].digest.to_hex(), "5b123b99225b5117bb7553929db40e8536bf84e687629bde05184cccbb734793");
+/// 	Ok(())
+/// }
+/// ```
+pub fn tree_hash<P: AsRef<Path>>(path: P, algorithm: HashAlgorithm, options: &TreeHashOptions) -> Result<TreeHash> {
+	let path = path.as_ref();
+	let metadata = fs::symlink_metadata(path).map_err(|e| tree_hash_error(path, e))?;
+	let mut nodes = Vec::new();
+	let digest = match hash_node(path, Path::new(""), &metadata, algorithm, options, &mut nodes)? {
+		Some((_, digest)) => digest,
+		None => return Err(PhollaitsError::new(
+			PhollaitsErrorKind::HashingError,
+			format!("Error while trying to hash tree {}; unsupported file type", path.display()))),
+	};
+	Ok(TreeHash { digest, nodes })
+}
+
+// returns the type and the digest of the node, or None for unsupported file types. The nodes will be appended in
+// canonical order, if they should be collected.
+fn hash_node(
+	path: &Path,
+	relative_path: &Path,
+	metadata: &fs::Metadata,
+	algorithm: HashAlgorithm,
+	options: &TreeHashOptions,
+	nodes: &mut Vec<TreeNode>) -> Result<Option<(TreeNodeType, Digest)>> {
+	let file_type = metadata.file_type();
+	let (node_type, digest, children) = if file_type.is_symlink() {
+		let target = fs::read_link(path).map_err(|e| tree_hash_error(path, e))?;
+		let mut hasher = Hasher::new(algorithm);
+		hasher.update(&link_target_bytes(&target));
+		(TreeNodeType::Symlink, hasher.finalize(algorithm), Vec::new())
+	} else if file_type.is_file() {
+		let mut file = fs::File::open(path).map_err(|e| tree_hash_error(path, e))?;
+		(TreeNodeType::File, file.digest_with(algorithm)?, Vec::new())
+	} else if file_type.is_dir() {
+		let (digest, children) = hash_directory(path, relative_path, algorithm, options)?;
+		(TreeNodeType::Directory, digest, children)
+	} else {
+		return Ok(None);
+	};
+	if options.collect_nodes {
+		nodes.push(TreeNode {
+			path: relative_path.to_path_buf(),
+			node_type,
+			digest: digest.clone(),
+		});
+		nodes.extend(children);
+	}
+	Ok(Some((node_type, digest)))
+}
+
+// returns the digest of the directory and the collected nodes of its children.
+fn hash_directory(
+	path: &Path,
+	relative_path: &Path,
+	algorithm: HashAlgorithm,
+	options: &TreeHashOptions) -> Result<(Digest, Vec<TreeNode>)> {
+	let mut children = Vec::new();
+	for entry in fs::read_dir(path).map_err(|e| tree_hash_error(path, e))? {
+		let entry = entry.map_err(|e| tree_hash_error(path, e))?;
+		children.push((name_bytes(&entry.file_name()), entry.file_name()));
+	}
+	children.sort();
+
+	let mut hasher = Hasher::new(algorithm);
+	let mut nodes = Vec::new();
+	for (name, file_name) in children {
+		let child_path = path.join(&file_name);
+		let metadata = fs::symlink_metadata(&child_path).map_err(|e| tree_hash_error(&child_path, e))?;
+		let (node_type, digest) = match hash_node(&child_path, &relative_path.join(&file_name), &metadata, algorithm, options, &mut nodes)? {
+			Some(x) => x,
+			None => continue,
+		};
+		hasher.update(&[node_type.type_byte()]);
+		hasher.update(&(name.len() as u64).to_be_bytes());
+		hasher.update(&name);
+		if options.include_permissions && node_type != TreeNodeType::Symlink {
+			hasher.update(&file_permissions(&metadata).to_be_bytes());
+		}
+		hasher.update(digest.as_bytes());
+	}
+	Ok((hasher.finalize(algorithm), nodes))
+}
+
+#[cfg(unix)]
+fn name_bytes(name: &std::ffi::OsStr) -> Vec<u8> {
+	use std::os::unix::ffi::OsStrExt;
+	name.as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn name_bytes(name: &std::ffi::OsStr) -> Vec<u8> {
+	name.to_string_lossy().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn link_target_bytes(target: &Path) -> Vec<u8> {
+	use std::os::unix::ffi::OsStrExt;
+	target.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn link_target_bytes(target: &Path) -> Vec<u8> {
+	target.to_string_lossy().replace('\\', "/").into_bytes()
+}
+
+fn tree_hash_error(path: &Path, e: io::Error) -> PhollaitsError {
+	PhollaitsError::new(
+		PhollaitsErrorKind::HashingError,
+		format!("Error while trying to hash tree {}; {}", path.display(), e))
+}